@@ -0,0 +1,232 @@
+use aho_corasick::AhoCorasick;
+use anyhow::{anyhow, bail};
+use aoc_2023::trace;
+use std::fmt;
+use std::io::BufRead;
+use std::ops::Range;
+
+// The spellings a decoder recognises, each standing for a digit.
+#[derive(Debug, Clone, Default)]
+pub struct Vocabulary {
+    words: Vec<(String, u32)>,
+}
+
+impl Vocabulary {
+    pub fn digits() -> Vocabulary {
+        Vocabulary {
+            words: (0..10).map(|d| (d.to_string(), d)).collect(),
+        }
+    }
+
+    pub fn english() -> Vocabulary {
+        Vocabulary::digits().with_words(&[
+            "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+        ])
+    }
+
+    pub fn german() -> Vocabulary {
+        Vocabulary::digits().with_words(&[
+            "eins", "zwei", "drei", "vier", "fünf", "sechs", "sieben", "acht", "neun",
+        ])
+    }
+
+    pub fn french() -> Vocabulary {
+        Vocabulary::digits().with_words(&[
+            "un", "deux", "trois", "quatre", "cinq", "six", "sept", "huit", "neuf",
+        ])
+    }
+
+    pub fn by_name(name: &str) -> anyhow::Result<Vocabulary> {
+        match name {
+            "digits" => Ok(Vocabulary::digits()),
+            "english" => Ok(Vocabulary::english()),
+            "german" => Ok(Vocabulary::german()),
+            "french" => Ok(Vocabulary::french()),
+            _ => Err(anyhow!("unknown vocabulary '{}'", name)),
+        }
+    }
+
+    // Adds the words for 1 through 9, in order
+    fn with_words(mut self, one_to_nine: &[&str]) -> Vocabulary {
+        self.words.extend(
+            (1..)
+                .zip(one_to_nine)
+                .map(|(d, word)| (word.to_string(), d)),
+        );
+        self
+    }
+
+    pub fn with_word(mut self, word: &str, digit: u32) -> Vocabulary {
+        self.words.push((word.to_string(), digit));
+        self
+    }
+
+    // Adds words from a spec like "uno=1,dos=2"
+    pub fn with_spec(mut self, spec: &str) -> anyhow::Result<Vocabulary> {
+        for entry in spec.split(',').filter(|entry| !entry.is_empty()) {
+            let (word, digit) = entry
+                .split_once('=')
+                .ok_or_else(|| anyhow!("expected 'word=digit' in '{}'", entry))?;
+            let digit = digit.trim().parse()?;
+            if word.trim().is_empty() || digit > 9 {
+                bail!("invalid vocabulary entry '{}'", entry);
+            }
+            self = self.with_word(word.trim(), digit);
+        }
+        Ok(self)
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct DigitMatch {
+    pub span: Range<usize>,
+    pub digit: u32,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Calibration {
+    pub first: DigitMatch,
+    pub last: DigitMatch,
+}
+
+impl Calibration {
+    pub fn value(&self) -> u32 {
+        self.first.digit * 10 + self.last.digit
+    }
+}
+
+impl fmt::Display for Calibration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} (first {} at {:?}, last {} at {:?})",
+            self.value(),
+            self.first.digit,
+            self.first.span,
+            self.last.digit,
+            self.last.span
+        )
+    }
+}
+
+// What to do with a line that holds no digits at all
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum NoDigits {
+    Skip,
+    Zero,
+    Error,
+}
+
+impl NoDigits {
+    pub fn by_name(name: &str) -> anyhow::Result<NoDigits> {
+        match name {
+            "skip" => Ok(NoDigits::Skip),
+            "zero" => Ok(NoDigits::Zero),
+            "error" => Ok(NoDigits::Error),
+            _ => Err(anyhow!("expected skip, zero or error, got '{}'", name)),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub struct Totals {
+    pub sum: u64,
+    // Lines that contributed a value, including zeroes
+    pub counted: usize,
+    pub skipped: usize,
+}
+
+// Finds every vocabulary word in a single pass, overlaps included, so "twone" yields both
+// "two" and "one".
+#[derive(Debug, Clone)]
+pub struct Decoder {
+    automaton: AhoCorasick,
+    digits: Vec<u32>,
+}
+
+impl Decoder {
+    pub fn new(vocabulary: &Vocabulary) -> anyhow::Result<Decoder> {
+        let automaton = AhoCorasick::new(vocabulary.words.iter().map(|(word, _)| word))?;
+        let digits = vocabulary.words.iter().map(|&(_, digit)| digit).collect();
+
+        Ok(Decoder { automaton, digits })
+    }
+
+    pub fn matches<'a>(&'a self, line: &'a str) -> impl Iterator<Item = DigitMatch> + 'a {
+        self.automaton
+            .find_overlapping_iter(line)
+            .map(|m| DigitMatch {
+                span: m.range(),
+                digit: self.digits[m.pattern().as_usize()],
+            })
+    }
+
+    // The earliest- and latest-starting matches, or None if the line holds no digits
+    pub fn calibrate(&self, line: &str) -> Option<Calibration> {
+        self.matches(line).fold(None, |found, m| match found {
+            None => Some(Calibration {
+                first: m.clone(),
+                last: m,
+            }),
+            Some(Calibration { first, last }) => Some(Calibration {
+                first: if m.span.start < first.span.start {
+                    m.clone()
+                } else {
+                    first
+                },
+                last: if m.span.start > last.span.start {
+                    m
+                } else {
+                    last
+                },
+            }),
+        })
+    }
+
+    // Sums the calibration values of every line in `reader`, holding one line at a time. In
+    // trace mode each line's calibration is reported on stderr.
+    pub fn sum_lines(
+        &self,
+        mut reader: impl BufRead,
+        no_digits: NoDigits,
+    ) -> anyhow::Result<Totals> {
+        let mut totals = Totals::default();
+        let mut line = String::new();
+        let mut line_no = 0;
+
+        while reader.read_line(&mut line)? > 0 {
+            line_no += 1;
+            let text = line.trim_end_matches(['\n', '\r']);
+
+            match self.calibrate(text) {
+                Some(calibration) => {
+                    if trace::enabled() {
+                        eprintln!("[day1] line {}: {}", line_no, calibration);
+                    }
+                    totals.sum += calibration.value() as u64;
+                    totals.counted += 1;
+                }
+                None => {
+                    let action = match no_digits {
+                        NoDigits::Error => bail!("line {} has no digits: '{}'", line_no, text),
+                        NoDigits::Zero => {
+                            totals.counted += 1;
+                            "counted as 0"
+                        }
+                        NoDigits::Skip => {
+                            totals.skipped += 1;
+                            "skipped"
+                        }
+                    };
+                    if trace::enabled() {
+                        eprintln!("[day1] line {}: no digits, {}", line_no, action);
+                    }
+                }
+            }
+
+            line.clear();
+        }
+
+        Ok(totals)
+    }
+}
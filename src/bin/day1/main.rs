@@ -1,57 +1,98 @@
+mod decoder;
+
+use aoc_2023::trace;
+use decoder::{Decoder, NoDigits, Vocabulary};
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+
 const INPUT: &str = include_str!("input.dat");
 
-fn pt1() {
-    let result: u32 = INPUT
-        .lines()
-        .map(|line| {
-            let mut digits = line.chars().filter_map(|c| c.to_digit(10));
-
-            let first = digits.next().unwrap();
-            first * 10 + digits.last().unwrap_or(first)
-        })
-        .sum();
-    println!("{}", result);
-}
-
-fn parse_digit(inp: &str) -> u32 {
-    match inp {
-        "one" => 1,
-        "two" => 2,
-        "three" => 3,
-        "four" => 4,
-        "five" => 5,
-        "six" => 6,
-        "seven" => 7,
-        "eight" => 8,
-        "nine" => 9,
-        _ => inp.parse().unwrap(),
+// Reads from `--file <path>` when given, otherwise the embedded input
+fn input_from_args() -> anyhow::Result<Box<dyn BufRead>> {
+    let mut args = std::env::args().skip_while(|arg| arg != "--file");
+    match args.nth(1) {
+        Some(path) => Ok(Box::new(BufReader::new(File::open(path)?))),
+        None => Ok(Box::new(INPUT.as_bytes())),
+    }
+}
+
+fn no_digits_from_args() -> anyhow::Result<NoDigits> {
+    let mut args = std::env::args().skip_while(|arg| arg != "--no-digits");
+    args.nth(1)
+        .map_or(Ok(NoDigits::Error), |name| NoDigits::by_name(&name))
+}
+
+fn calibration_sum(decoder: &Decoder) -> anyhow::Result<u64> {
+    let totals = decoder.sum_lines(input_from_args()?, no_digits_from_args()?)?;
+    if trace::enabled() {
+        eprintln!(
+            "[day1] {} lines counted, {} skipped",
+            totals.counted, totals.skipped
+        );
+    }
+    Ok(totals.sum)
+}
+
+// Picks the part 2 vocabulary from `--vocab <name>` and `--words <word=digit,...>`
+fn vocabulary_from_args() -> anyhow::Result<Vocabulary> {
+    let args: Vec<String> = std::env::args().collect();
+    let value = |flag: &str| {
+        args.iter()
+            .position(|arg| arg == flag)
+            .and_then(|i| args.get(i + 1))
+    };
+
+    let vocabulary = match value("--vocab") {
+        Some(name) => Vocabulary::by_name(name)?,
+        None => Vocabulary::english(),
+    };
+    match value("--words") {
+        Some(spec) => vocabulary.with_spec(spec),
+        None => Ok(vocabulary),
     }
 }
 
-fn pt2() {
-    let digit_re =
-        regex::Regex::new(r"^(\d|one|two|three|four|five|six|seven|eight|nine)").unwrap();
+fn spans(decoder: &Decoder) -> anyhow::Result<()> {
+    for line in input_from_args()?.lines() {
+        let line = line?;
+        match decoder.calibrate(&line) {
+            Some(calibration) => println!("{}: {}", line, calibration),
+            None => println!("{}: no digits", line),
+        }
+    }
+    Ok(())
+}
 
-    let result: u32 = INPUT
-        .lines()
-        .map(|line| {
-            let mut digits = (0..line.len()).filter_map(|i| {
-                digit_re
-                    .captures(&line[i..])
-                    .map(|capture| parse_digit(&capture[1]))
-            });
+// Bad input, such as a line without digits, ends the run with a message rather than a panic
+fn print_calibration_sum(decoder: &Decoder) {
+    match calibration_sum(decoder) {
+        Ok(result) => println!("{}", result),
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    }
+}
 
-            let first = digits.next().unwrap();
-            first * 10 + digits.last().unwrap_or(first)
-        })
-        .sum();
+fn pt1() {
+    let decoder = Decoder::new(&Vocabulary::digits()).unwrap();
+    print_calibration_sum(&decoder);
+}
 
-    println!("{}", result);
+fn pt2(vocabulary: &Vocabulary) {
+    let decoder = Decoder::new(vocabulary).unwrap();
+    print_calibration_sum(&decoder);
 }
 
 fn main() {
+    let vocabulary = vocabulary_from_args().unwrap();
+    if std::env::args().any(|arg| arg == "--spans") {
+        spans(&Decoder::new(&vocabulary).unwrap()).unwrap();
+        return;
+    }
+
     println!("Part 1:");
     pt1();
     println!("Part 2:");
-    pt2();
+    pt2(&vocabulary);
 }
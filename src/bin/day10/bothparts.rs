@@ -1,8 +1,12 @@
 use super::*;
+use anyhow::{anyhow, bail};
+use aoc_2023::geometry::Polygon;
+use aoc_2023::grid::Grid;
+use aoc_2023::render::{self, Image};
 use lazy_static::lazy_static;
 use regex::Regex;
 use std::collections::{HashMap, HashSet, VecDeque};
-use std::convert::Infallible;
+use std::path::Path;
 use std::str::FromStr;
 
 const INPUT: &str = include_str!("input.dat");
@@ -14,6 +18,8 @@ const SAMPLE2: &str = include_str!("sample2.dat");
 #[derive(Debug, Clone)]
 struct Map {
     pipes: Vec<Vec<Pipe>>,
+    // S, whose tile in `pipes` holds the pipe inferred from its neighbours
+    start: (usize, usize),
 }
 
 impl Map {
@@ -58,15 +64,40 @@ impl Map {
     }
 
     fn find_start(&self) -> (usize, usize) {
-        for x in 0..self.width() {
-            for y in 0..self.height() {
-                if self.pipes[y][x] == Pipe::Start {
-                    return (x, y);
-                }
-            }
-        }
+        self.start
+    }
 
-        panic!("No start found");
+    fn start_pipe(&self) -> Pipe {
+        let (x, y) = self.start;
+        self.pipes[y][x]
+    }
+
+    // S joins up with each neighbour that points back at it, and must have exactly two
+    fn infer_start_pipe(&self) -> anyhow::Result<Pipe> {
+        let (x, y) = self.start;
+        let up = y != 0 && self.pipes[y - 1][x].points_down();
+        let down = y + 1 < self.height() && self.pipes[y + 1][x].points_up();
+        let left = x != 0 && self.pipes[y][x - 1].points_right();
+        let right = x + 1 < self.width() && self.pipes[y][x + 1].points_left();
+
+        let pipe = match (up, down, left, right) {
+            (true, true, false, false) => Pipe::Vert,
+            (false, false, true, true) => Pipe::Hori,
+            (true, false, false, true) => Pipe::NE,
+            (true, false, true, false) => Pipe::NW,
+            (false, true, false, true) => Pipe::SE,
+            (false, true, true, false) => Pipe::SW,
+            _ => {
+                let count = [up, down, left, right].into_iter().filter(|&c| c).count();
+                bail!(
+                    "S at ({}, {}) has {} connecting neighbours, expected 2",
+                    x,
+                    y,
+                    count
+                );
+            }
+        };
+        Ok(pipe)
     }
 
     fn find_furthest_dist_from_start(&self) -> u64 {
@@ -94,123 +125,73 @@ impl Map {
         max
     }
 
-    fn find_loop_tiles(&self) -> HashSet<(usize, usize)> {
+    fn find_loop(&self) -> anyhow::Result<Vec<(usize, usize)>> {
         let start = self.find_start();
 
-        let mut visited = HashSet::new();
-        let mut to_visit: VecDeque<((usize, usize), u64)> =
-            [(start.clone(), 0)].into_iter().collect();
-
-        while !to_visit.is_empty() {
-            let ((x, y), dist) = to_visit.pop_front().unwrap();
-
-            visited.insert((x, y));
-            let adjacent = self.adjacent_pipes(x, y);
-            for (x, y) in adjacent {
-                if !visited.contains(&(x, y)) {
-                    to_visit.push_back(((x, y), dist + 1));
-                }
-            }
-        }
-
-        visited
-    }
-
-    fn find_num_outside_loop(&self) -> u64 {
-        let loop_tiles = self.find_loop_tiles();
-
-        #[derive(Debug, Copy, Clone, Eq, PartialEq)]
-        enum Status {
-            Loop,
-            Outside,
-            Unknown,
-        }
-
-        fn overlay_pos(x: usize, y: usize) -> (usize, usize) {
-            (x * 2 + 1, y * 2 + 1)
-        }
-
-        // Create an overlay that intersperses a tile between each actual tile
-        let mut statuses: Vec<Vec<Status>> =
-            vec![vec![Status::Unknown; self.width() * 2 + 1]; self.height() * 2 + 1];
-
-        for (x, y) in loop_tiles {
-            let curr_pipe = self.pipes[y][x];
-            let (ox, oy) = overlay_pos(x, y);
-
-            statuses[oy][ox] = Status::Loop;
-            if curr_pipe.points_up() && y != 0 && self.pipes[y - 1][x].points_down() {
-                statuses[oy - 1][ox] = Status::Loop;
-            }
-
-            if curr_pipe.points_down() && y + 1 < self.height() && self.pipes[y + 1][x].points_up()
-            {
-                statuses[oy + 1][ox] = Status::Loop;
+        let mut path = vec![start];
+        let mut prev = start;
+        let mut curr = start;
+        loop {
+            let next = self
+                .adjacent_pipes(curr.0, curr.1)
+                .into_iter()
+                .find(|&adjacent| adjacent != prev)
+                .ok_or_else(|| anyhow!("loop from S dead-ends at {:?}", curr))?;
+
+            if next == start {
+                break;
             }
 
-            if curr_pipe.points_left() && x != 0 && self.pipes[y][x - 1].points_right() {
-                statuses[oy][ox - 1] = Status::Loop;
-            }
-
-            if curr_pipe.points_right()
-                && x + 1 < self.width()
-                && self.pipes[y][x + 1].points_left()
-            {
-                statuses[oy][ox + 1] = Status::Loop;
-            }
+            path.push(next);
+            prev = curr;
+            curr = next;
         }
 
-        let mut to_infect: VecDeque<(usize, usize)> = [(0, 0)].into_iter().collect();
-        while !to_infect.is_empty() {
-            let (cx, cy) = to_infect.pop_front().unwrap();
-
-            if cx != 0 && statuses[cy][cx - 1] == Status::Unknown {
-                statuses[cy][cx - 1] = Status::Outside;
-                to_infect.push_back((cx - 1, cy));
-            }
-            if cx != statuses[0].len() - 1 && statuses[cy][cx + 1] == Status::Unknown {
-                statuses[cy][cx + 1] = Status::Outside;
-                to_infect.push_back((cx + 1, cy));
-            }
-
-            if cy != 0 && statuses[cy - 1][cx] == Status::Unknown {
-                statuses[cy - 1][cx] = Status::Outside;
-                to_infect.push_back((cx, cy - 1));
-            }
-            if cy != statuses.len() - 1 && statuses[cy + 1][cx] == Status::Unknown {
-                statuses[cy + 1][cx] = Status::Outside;
-                to_infect.push_back((cx, cy + 1));
-            }
-        }
+        Ok(path)
+    }
 
-        let mut count = 0;
+    fn find_num_enclosed_by_loop(&self) -> anyhow::Result<u64> {
+        let vertices = self
+            .find_loop()?
+            .into_iter()
+            .map(|(x, y)| (x as i64, y as i64))
+            .collect();
 
-        for y in 0..self.height() {
-            for x in 0..self.width() {
-                let (ox, oy) = overlay_pos(x, y);
-                if statuses[oy][ox] == Status::Unknown {
-                    count += 1;
-                }
-            }
-        }
-        count
+        Ok(Polygon::new(vertices).interior_points())
     }
 }
 
 impl FromStr for Map {
-    type Err = Infallible;
+    type Err = anyhow::Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut starts = Vec::new();
         let pipes = s
             .lines()
-            .map(|line| {
+            .enumerate()
+            .map(|(y, line)| {
                 line.chars()
-                    .map(|c| c.to_string().parse().unwrap())
+                    .enumerate()
+                    .map(|(x, c)| {
+                        if c == 'S' {
+                            starts.push((x, y));
+                            Ok(Pipe::Ground)
+                        } else {
+                            c.to_string().parse()
+                        }
+                    })
                     .collect()
             })
-            .collect();
+            .collect::<anyhow::Result<_>>()?;
+
+        let [start] = starts[..] else {
+            bail!("expected exactly one S, found {}", starts.len());
+        };
 
-        Ok(Map { pipes })
+        let mut map = Map { pipes, start };
+        let (x, y) = start;
+        map.pipes[y][x] = map.infer_start_pipe()?;
+        Ok(map)
     }
 }
 
@@ -223,7 +204,6 @@ enum Pipe {
     SE,
     SW,
     Ground,
-    Start,
 }
 
 impl Pipe {
@@ -236,7 +216,6 @@ impl Pipe {
             Pipe::SE => false,
             Pipe::SW => false,
             Pipe::Ground => false,
-            Pipe::Start => true,
         }
     }
 
@@ -249,7 +228,6 @@ impl Pipe {
             Pipe::SE => true,
             Pipe::SW => true,
             Pipe::Ground => false,
-            Pipe::Start => true,
         }
     }
 
@@ -262,7 +240,6 @@ impl Pipe {
             Pipe::SE => false,
             Pipe::SW => true,
             Pipe::Ground => false,
-            Pipe::Start => true,
         }
     }
 
@@ -275,13 +252,26 @@ impl Pipe {
             Pipe::SE => true,
             Pipe::SW => false,
             Pipe::Ground => false,
-            Pipe::Start => true,
+        }
+    }
+}
+
+impl Pipe {
+    fn symbol(&self) -> char {
+        match self {
+            Pipe::Vert => '|',
+            Pipe::Hori => '-',
+            Pipe::NE => 'L',
+            Pipe::NW => 'J',
+            Pipe::SW => '7',
+            Pipe::SE => 'F',
+            Pipe::Ground => '.',
         }
     }
 }
 
 impl FromStr for Pipe {
-    type Err = Infallible;
+    type Err = anyhow::Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s {
@@ -292,8 +282,7 @@ impl FromStr for Pipe {
             "7" => Ok(Pipe::SW),
             "F" => Ok(Pipe::SE),
             "." => Ok(Pipe::Ground),
-            "S" => Ok(Pipe::Start),
-            _ => panic!("unknown pipe {}", s),
+            _ => Err(anyhow!("unknown pipe {}", s)),
         }
     }
 }
@@ -302,6 +291,29 @@ lazy_static! {
     static ref EXAMPLE: Regex = Regex::new(r"example").unwrap();
 }
 
+pub fn render(dir: &Path) -> anyhow::Result<()> {
+    let map = Map::from_str(INPUT).unwrap();
+    let loop_tiles: HashSet<(usize, usize)> = map.find_loop()?.into_iter().collect();
+
+    let grid = Grid::from_fn(map.width(), map.height(), |x, y| {
+        match (map.pipes[y][x], loop_tiles.contains(&(x, y))) {
+            _ if (x, y) == map.start => render::RED,
+            (_, true) => render::YELLOW,
+            (Pipe::Ground, false) => render::BLACK,
+            (_, false) => render::GREY,
+        }
+    });
+
+    std::fs::create_dir_all(dir)?;
+    Image::from_grid(&grid, 4, |&color| color).write(&dir.join("day10_loop.png"))
+}
+
+pub fn start() {
+    let map = Map::from_str(INPUT).unwrap();
+    let (x, y) = map.find_start();
+    println!("S at ({}, {}) is {}", x, y, map.start_pipe().symbol());
+}
+
 pub fn pt1() {
     let map = Map::from_str(INPUT).unwrap();
     let result = map.find_furthest_dist_from_start();
@@ -311,7 +323,8 @@ pub fn pt1() {
 
 pub fn pt2() {
     let map = Map::from_str(INPUT).unwrap();
-    let result = map.find_num_outside_loop();
-
-    println!("{}", result);
+    match map.find_num_enclosed_by_loop() {
+        Ok(result) => println!("{}", result),
+        Err(e) => eprintln!("{}", e),
+    }
 }
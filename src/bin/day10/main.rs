@@ -1,7 +1,18 @@
+use aoc_2023::render::render_dir_from_args;
+
 mod bothparts;
 // mod pt2;
 
 fn main() {
+    if let Some(dir) = render_dir_from_args() {
+        bothparts::render(&dir).unwrap();
+        return;
+    }
+    if std::env::args().any(|arg| arg == "--start") {
+        bothparts::start();
+        return;
+    }
+
     println!("Part 1:");
     bothparts::pt1();
     println!("Part 2:");
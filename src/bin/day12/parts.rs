@@ -1,9 +1,9 @@
 use super::*;
+use aoc_2023::memo::{Memo, MemoStats};
 use itertools::Itertools;
 use lazy_static::lazy_static;
 use rayon::prelude::*;
 use regex::Regex;
-use std::collections::{HashMap, HashSet, VecDeque};
 use std::convert::Infallible;
 use std::str::FromStr;
 
@@ -33,71 +33,63 @@ impl ToString for SpringLine {
 }
 
 impl SpringLine {
-    fn num_arrangements(&self, memoize: &mut HashMap<SpringLine, usize>) -> usize {
-        if memoize.contains_key(&self) {
-            return memoize[&self];
-        }
+    fn num_arrangements(&self) -> (usize, MemoStats) {
+        let mut memo = Memo::new();
+        let result = self.arrangements_from(0, 0, &mut memo);
 
-        // If there are no springs but groups, we don't match
-        if !self.groups.is_empty() && self.springs.len() < *self.groups.iter().next().unwrap() {
-            return 0;
-        }
+        (result, memo.stats())
+    }
 
-        // If there are no more groups left, all ? must be .
-        if self.groups.is_empty() {
-            if self.springs.contains('#') {
-                return 0;
-            } else {
-                return 1;
+    // Counts the arrangements of `springs[pos..]` that match `groups[group..]`.
+    fn arrangements_from(
+        &self,
+        pos: usize,
+        group: usize,
+        memo: &mut Memo<(usize, usize), usize>,
+    ) -> usize {
+        memo.cached((pos, group), |memo| {
+            let springs = &self.springs.as_bytes()[pos..];
+            let groups = &self.groups[group..];
+
+            // If there are no more groups left, all ? must be .
+            if groups.is_empty() {
+                return if springs.contains(&b'#') { 0 } else { 1 };
             }
-        }
 
-        // Trim leading '.' characters
-        let trimmed = self.springs.trim_start_matches('.');
-        if trimmed != self.springs {
-            return SpringLine {
-                springs: trimmed.to_string(),
-                groups: self.groups.clone(),
+            // If there are no springs but groups, we don't match
+            if springs.len() < groups[0] {
+                return 0;
             }
-            .num_arrangements(memoize);
-        }
-        let mut chars = self.springs.chars();
-        let first_char = chars.next().unwrap();
-
-        let mut groups = self.groups.iter();
-        let next_group = groups.next().unwrap();
-
-        let result = if first_char == '#' {
-            // This must match the next group
-            let mut rest = (&mut chars).take(next_group - 1);
-            if rest.any(|c| c == '.') || chars.next() == Some('#') {
-                0
-            } else {
-                SpringLine {
-                    springs: chars.collect(),
-                    groups: groups.cloned().collect(),
+
+            match springs[0] {
+                b'.' => self.arrangements_from(pos + 1, group, memo),
+                b'#' => self.arrangements_with_group_at(pos, group, memo),
+                b'?' => {
+                    self.arrangements_from(pos + 1, group, memo)
+                        + self.arrangements_with_group_at(pos, group, memo)
                 }
-                .num_arrangements(memoize)
-            }
-        } else if first_char == '?' {
-            let dot_answer = SpringLine {
-                springs: chars.clone().collect(),
-                groups: self.groups.clone(),
+                _ => unreachable!(),
             }
-            .num_arrangements(memoize);
-            let spring_answer = SpringLine {
-                springs: ['#'].into_iter().chain(chars).collect(),
-                groups: self.groups.clone(),
-            }
-            .num_arrangements(memoize);
+        })
+    }
 
-            dot_answer + spring_answer
-        } else {
-            unreachable!()
-        };
+    // Counts the arrangements where the next group starts exactly at `pos`.
+    fn arrangements_with_group_at(
+        &self,
+        pos: usize,
+        group: usize,
+        memo: &mut Memo<(usize, usize), usize>,
+    ) -> usize {
+        let springs = &self.springs.as_bytes()[pos..];
+        let len = self.groups[group];
+
+        // This must match the next group, and be followed by a gap
+        if springs[..len].contains(&b'.') || springs.get(len) == Some(&b'#') {
+            return 0;
+        }
 
-        memoize.insert(self.clone(), result);
-        result
+        let next = usize::min(pos + len + 1, self.springs.len());
+        self.arrangements_from(next, group + 1, memo)
     }
 
     fn unfolded_n(&self, n: usize) -> SpringLine {
@@ -156,21 +148,27 @@ lazy_static! {
 pub fn pt1() {
     let spring_map: Map = INPUT.parse().unwrap();
 
-    let result: usize = spring_map
+    let (counts, stats): (Vec<usize>, Vec<MemoStats>) = spring_map
         .lines
         .par_iter()
-        .map(|line| line.num_arrangements(&mut HashMap::new()))
-        .sum();
+        .map(|line| line.num_arrangements())
+        .unzip();
+    stats.into_iter().sum::<MemoStats>().report("day12 pt1");
+
+    let result: usize = counts.into_iter().sum();
     println!("{}", result);
 }
 
 pub fn pt2() {
     let spring_map: Map = INPUT.parse().unwrap();
 
-    let result: usize = spring_map
+    let (counts, stats): (Vec<usize>, Vec<MemoStats>) = spring_map
         .lines
         .par_iter()
-        .map(|line| line.unfolded_n(5).num_arrangements(&mut HashMap::new()))
-        .sum();
+        .map(|line| line.unfolded_n(5).num_arrangements())
+        .unzip();
+    stats.into_iter().sum::<MemoStats>().report("day12 pt2");
+
+    let result: usize = counts.into_iter().sum();
     println!("{}", result);
 }
@@ -1,4 +1,5 @@
 use super::*;
+use aoc_2023::parse;
 use lazy_static::lazy_static;
 use regex::Regex;
 use std::collections::{HashMap, HashSet};
@@ -12,11 +13,7 @@ const SAMPLE: &str = include_str!("sample2.dat");
 type Pattern = Vec<Vec<char>>;
 
 fn parse_patterns(input: &str) -> Vec<Pattern> {
-    input
-        .trim()
-        .split("\n\n")
-        .map(|pattern| pattern.lines().map(|line| line.chars().collect()).collect())
-        .collect()
+    parse::blocks(input).map(parse::char_grid).collect()
 }
 
 fn find_vertical_refl_site(pattern: &Pattern) -> Vec<Window> {
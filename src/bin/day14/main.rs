@@ -1,6 +1,13 @@
+use aoc_2023::render::render_dir_from_args;
+
 mod pt1;
 
 fn main() {
+    if let Some(dir) = render_dir_from_args() {
+        pt1::render(&dir).unwrap();
+        return;
+    }
+
     println!("Part 1:");
     pt1::pt1();
     println!("Part 2:");
@@ -1,4 +1,9 @@
-use std::collections::{BTreeSet, HashMap};
+use aoc_2023::bitgrid::BitGrid;
+use aoc_2023::grid::Grid;
+use aoc_2023::memo::Memo;
+use aoc_2023::render::{self, FrameWriter, Image, Palette};
+use std::collections::HashSet;
+use std::path::Path;
 use std::rc::Rc;
 
 const INPUT: &str = include_str!("input.dat");
@@ -7,8 +12,8 @@ const SAMPLE: &str = include_str!("sample.dat");
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
 struct Map {
-    rocks: PosMap,
-    obstacles: PosMap,
+    rocks: BitGrid,
+    obstacles: BitGrid,
     width: usize,
     height: usize,
 }
@@ -17,19 +22,14 @@ impl Map {
     fn get(&self, x: usize, y: usize) -> char {
         if x >= self.width || y >= self.height {
             panic!()
-        } else if self.rocks.contains(&(x, y)) {
+        } else if self.rocks.get(x, y) {
             'O'
-        } else if self.obstacles.contains(&(x, y)) {
+        } else if self.obstacles.get(x, y) {
             '#'
         } else {
             '.'
         }
     }
-
-    fn move_rock(&mut self, orig: (usize, usize), new: (usize, usize)) {
-        self.rocks.remove(&orig);
-        self.rocks.insert(new);
-    }
 }
 
 impl ToString for Map {
@@ -45,20 +45,18 @@ impl ToString for Map {
     }
 }
 
-type PosMap = BTreeSet<(usize, usize)>;
-
 fn load_map(input: &str) -> Map {
     let char_map: Vec<Vec<char>> = input.trim().lines().map(|l| l.chars().collect()).collect();
-    let mut rocks = BTreeSet::new();
-    let mut obstacles = BTreeSet::new();
     let width = char_map[0].len();
     let height = char_map.len();
+    let mut rocks = BitGrid::new(width, height);
+    let mut obstacles = BitGrid::new(width, height);
     for x in 0..width {
         for y in 0..height {
             if char_map[y][x] == '#' {
-                obstacles.insert((x, y));
+                obstacles.insert(x, y);
             } else if char_map[y][x] == 'O' {
-                rocks.insert((x, y));
+                rocks.insert(x, y);
             }
         }
     }
@@ -82,94 +80,149 @@ enum Direction {
 fn tilt(
     map: &Rc<Map>,
     dir: Direction,
-    memoized: &mut HashMap<(Rc<Map>, Direction), Rc<Map>>,
+    memoized: &mut Memo<(Rc<Map>, Direction), Rc<Map>>,
 ) -> (Rc<Map>, bool) {
-    if memoized.contains_key(&(map.clone(), dir)) {
-        return (memoized[&(map.clone(), dir)].clone(), true);
+    let key = (map.clone(), dir);
+    if let Some(tilted) = memoized.get(&key) {
+        return (tilted, true);
     };
 
     let mut nmap = (**map).clone();
 
-    let x_range: Box<dyn Iterator<Item = usize>> = match dir {
-        Direction::East => Box::new((0..map.width).rev()),
-        _ => Box::new(0..map.width),
-    };
-
-    for x in x_range {
-        let y_range: Box<dyn Iterator<Item = usize>> = match dir {
-            Direction::South => Box::new((0..map.height).rev()),
-            _ => Box::new(0..map.height),
-        };
-        for y in y_range {
-            if nmap.get(x, y) == 'O' {
-                let rock_loc = (x, y);
-                let new = move_rock_dir(&nmap, rock_loc, dir);
-                nmap.move_rock(rock_loc, new);
-            }
+    match dir {
+        Direction::North => tilt_vertical(&mut nmap, &(0..map.height).collect::<Vec<_>>()),
+        Direction::South => tilt_vertical(&mut nmap, &(0..map.height).rev().collect::<Vec<_>>()),
+        Direction::West | Direction::East => {
+            (0..map.height).for_each(|y| tilt_row(&mut nmap, y, dir))
         }
     }
 
     let nmap = Rc::new(nmap);
-    memoized.insert((map.clone(), dir), nmap.clone());
+    memoized.insert(key, nmap.clone());
     (nmap, false)
 }
 
-fn move_rock_dir_once(
-    map: &Map,
-    rock_loc: (usize, usize),
-    dir: Direction,
-) -> Option<(usize, usize)> {
-    let (x, y) = rock_loc;
-
-    match (y, dir) {
-        (0, Direction::North) => return None,
-        (y, Direction::South) if y == map.height - 1 => return None,
-        _ => (),
-    }
-
-    match (x, dir) {
-        (0, Direction::West) => return None,
-        (x, Direction::East) if x == map.width - 1 => return None,
-        _ => (),
+// Slides the rocks between rows, with `rows[0]` the edge they roll toward. Each word holds 64
+// columns, which all move together. Rows are settled in order, and each row's rocks then slide
+// over the settled rows until something blocks them.
+fn tilt_vertical(map: &mut Map, rows: &[usize]) {
+    for i in 0..map.width.div_ceil(64) {
+        for k in 1..rows.len() {
+            let mut moving = map.rocks.row_words(rows[k])[i];
+            let mut r = k;
+            while moving != 0 && r > 0 {
+                let (from, to) = (rows[r], rows[r - 1]);
+                moving &= !(map.rocks.row_words(to)[i] | map.obstacles.row_words(to)[i]);
+                map.rocks.row_words_mut(from)[i] &= !moving;
+                map.rocks.row_words_mut(to)[i] |= moving;
+                r -= 1;
+            }
+        }
     }
+}
 
-    let new_loc = match dir {
-        Direction::North => (rock_loc.0, rock_loc.1 - 1),
-        Direction::South => (rock_loc.0, rock_loc.1 + 1),
-        Direction::East => (rock_loc.0 + 1, rock_loc.1),
-        Direction::West => (rock_loc.0 - 1, rock_loc.1),
-    };
+// Moves every bit of a row one place toward x = 0
+fn shift_down(words: &[u64]) -> Vec<u64> {
+    (0..words.len())
+        .map(|i| words[i] >> 1 | words.get(i + 1).map_or(0, |next| next << 63))
+        .collect()
+}
 
-    let (new_x, new_y) = new_loc;
-    let new_char = map.get(new_x, new_y);
-    (new_char == '.').then_some(new_loc)
+// Moves every bit of a row one place away from x = 0
+fn shift_up(words: &[u64]) -> Vec<u64> {
+    (0..words.len())
+        .map(|i| words[i] << 1 | if i > 0 { words[i - 1] >> 63 } else { 0 })
+        .collect()
 }
 
-fn move_rock_dir(map: &Map, mut rock_loc: (usize, usize), dir: Direction) -> (usize, usize) {
-    while let Some(new_loc) = move_rock_dir_once(map, rock_loc, dir) {
-        rock_loc = new_loc;
+// Slides the rocks along row `y` toward the west or east edge. Every rock with room moves one
+// place per round, so the row settles within `width` rounds.
+fn tilt_row(map: &mut Map, y: usize, dir: Direction) {
+    // Rocks only ever move into free cells, so `free` just swaps the cells they leave and enter
+    let mut free: Vec<u64> = map
+        .rocks
+        .row_words(y)
+        .iter()
+        .zip(map.obstacles.row_words(y))
+        .enumerate()
+        .map(|(i, (rocks, obstacles))| !(rocks | obstacles) & map.rocks.width_mask(i))
+        .collect();
+
+    loop {
+        // Rocks whose neighbour in the direction of travel is free
+        let room = match dir {
+            Direction::West => shift_up(&free),
+            Direction::East => shift_down(&free),
+            _ => unreachable!(),
+        };
+        let movable: Vec<u64> = map
+            .rocks
+            .row_words(y)
+            .iter()
+            .zip(room)
+            .map(|(rocks, room)| rocks & room)
+            .collect();
+        if movable.iter().all(|&w| w == 0) {
+            break;
+        }
+
+        let moved = match dir {
+            Direction::West => shift_down(&movable),
+            _ => shift_up(&movable),
+        };
+        let rocks = map.rocks.row_words_mut(y);
+        for i in 0..rocks.len() {
+            rocks[i] = rocks[i] & !movable[i] | moved[i];
+            free[i] = free[i] & !moved[i] | movable[i];
+        }
     }
-    rock_loc
 }
 
 fn score_map(map: &Map) -> u64 {
     (0..map.height)
-        .flat_map(move |y| (0..map.width).map(move |x| (x, y)))
-        .filter(|&(x, y)| map.get(x, y) == 'O')
-        .map(|(x, y)| (map.height - y) as u64)
+        .map(|y| (map.rocks.count_row(y) * (map.height - y)) as u64)
         .sum()
 }
 
+// Writes a frame after every tilt of the spin cycle, stopping once a cycle repeats.
+pub fn render(dir: &Path) -> anyhow::Result<()> {
+    let palette = Palette::new([('O', render::WHITE), ('#', render::GREY)], render::BLACK);
+    let mut frames = FrameWriter::new(dir, "day14", "png")?;
+    let mut write_frame = |map: &Map| {
+        let grid = Grid::from_fn(map.width, map.height, |x, y| map.get(x, y));
+        frames.write(&Image::from_grid(&grid, 4, |c| palette.color(c)))
+    };
+
+    let mut map = Rc::new(load_map(INPUT));
+    let mut memoized = Memo::new();
+    let mut seen = HashSet::new();
+    write_frame(&map)?;
+
+    while seen.insert(map.clone()) {
+        for dir in [
+            Direction::North,
+            Direction::West,
+            Direction::South,
+            Direction::East,
+        ] {
+            (map, _) = tilt(&map, dir, &mut memoized);
+            write_frame(&map)?;
+        }
+    }
+
+    Ok(())
+}
+
 pub fn pt1() {
     let map = Rc::new(load_map(INPUT));
-    let (map, _) = tilt(&map, Direction::North, &mut HashMap::new());
+    let (map, _) = tilt(&map, Direction::North, &mut Memo::new());
     let result = score_map(&map);
     println!("{}", result);
 }
 
 pub fn pt2() {
     let mut map = Rc::new(load_map(INPUT));
-    let mut memoized = HashMap::new();
+    let mut memoized = Memo::new();
 
     let mut fin;
 
@@ -199,6 +252,8 @@ pub fn pt2() {
             loops.push(map.clone());
         }
     }
+    memoized.stats().report("day14 tilts");
+
     let remaining_loops = 1000000000 - (so_far + 1);
     let map = loops[remaining_loops % loop_len].clone();
     let result = score_map(&map);
@@ -1,5 +1,12 @@
+use aoc_2023::render::render_dir_from_args;
+
 mod parts;
 fn main() {
+    if let Some(dir) = render_dir_from_args() {
+        parts::render(&dir).unwrap();
+        return;
+    }
+
     println!("Part 1:");
     parts::pt1();
     println!("Part 2:");
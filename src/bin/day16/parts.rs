@@ -1,7 +1,11 @@
+use aoc_2023::bitgrid::BitGrid;
+use aoc_2023::grid::Grid;
+use aoc_2023::render::{self, Image};
 use lazy_static::lazy_static;
 use regex::Regex;
-use std::collections::{HashSet, VecDeque};
+use std::collections::VecDeque;
 use std::convert::Infallible;
+use std::path::Path;
 use std::str::FromStr;
 
 const INPUT: &str = include_str!("input.dat");
@@ -71,8 +75,8 @@ fn modify_dir(tiles: &Tiles, x: i64, y: i64, dir: Direction) -> Option<(usize, u
     }
 }
 
-fn follow_light_beam_once(tiles: &Tiles, light: Light) -> HashSet<Light> {
-    let mut results = HashSet::new();
+fn follow_light_beam_once(tiles: &Tiles, light: Light) -> Vec<Light> {
+    let mut results = Vec::with_capacity(2);
     let curr_tile = tiles.get(light.pos.0, light.pos.1);
 
     let (x, y) = (light.pos.0 as i64, light.pos.1 as i64);
@@ -80,7 +84,7 @@ fn follow_light_beam_once(tiles: &Tiles, light: Light) -> HashSet<Light> {
     match curr_tile {
         '.' => {
             modify_dir(tiles, x, y, light.direction).and_then(|pos| {
-                results.insert(Light {
+                results.push(Light {
                     pos,
                     direction: light.direction,
                 });
@@ -95,7 +99,7 @@ fn follow_light_beam_once(tiles: &Tiles, light: Light) -> HashSet<Light> {
                 Right => Up,
             };
             modify_dir(tiles, x, y, new_dir).and_then(|pos| {
-                results.insert(Light {
+                results.push(Light {
                     pos,
                     direction: new_dir,
                 });
@@ -110,7 +114,7 @@ fn follow_light_beam_once(tiles: &Tiles, light: Light) -> HashSet<Light> {
                 Right => Down,
             };
             modify_dir(tiles, x, y, new_dir).and_then(|pos| {
-                results.insert(Light {
+                results.push(Light {
                     pos,
                     direction: new_dir,
                 });
@@ -121,7 +125,7 @@ fn follow_light_beam_once(tiles: &Tiles, light: Light) -> HashSet<Light> {
             match light.direction {
                 Up | Down => {
                     modify_dir(tiles, x, y, light.direction).and_then(|pos| {
-                        results.insert(Light {
+                        results.push(Light {
                             pos,
                             direction: light.direction,
                         });
@@ -130,11 +134,11 @@ fn follow_light_beam_once(tiles: &Tiles, light: Light) -> HashSet<Light> {
                 }
                 Left | Right => {
                     modify_dir(tiles, x, y, Up).and_then(|pos| {
-                        results.insert(Light { pos, direction: Up });
+                        results.push(Light { pos, direction: Up });
                         Some(())
                     });
                     modify_dir(tiles, x, y, Down).and_then(|pos| {
-                        results.insert(Light {
+                        results.push(Light {
                             pos,
                             direction: Down,
                         });
@@ -146,7 +150,7 @@ fn follow_light_beam_once(tiles: &Tiles, light: Light) -> HashSet<Light> {
         '-' => match light.direction {
             Right | Left => {
                 modify_dir(tiles, x, y, light.direction).and_then(|pos| {
-                    results.insert(Light {
+                    results.push(Light {
                         pos,
                         direction: light.direction,
                     });
@@ -155,14 +159,14 @@ fn follow_light_beam_once(tiles: &Tiles, light: Light) -> HashSet<Light> {
             }
             Up | Down => {
                 modify_dir(tiles, x, y, Left).and_then(|pos| {
-                    results.insert(Light {
+                    results.push(Light {
                         pos,
                         direction: Left,
                     });
                     Some(())
                 });
                 modify_dir(tiles, x, y, Right).and_then(|pos| {
-                    results.insert(Light {
+                    results.push(Light {
                         pos,
                         direction: Right,
                     });
@@ -176,34 +180,56 @@ fn follow_light_beam_once(tiles: &Tiles, light: Light) -> HashSet<Light> {
     results
 }
 
-fn get_energized(tiles: &Tiles, starting_light: Light) -> HashSet<(usize, usize)> {
-    let mut occupied_tiles = HashSet::new();
-    occupied_tiles.insert(starting_light.pos);
+fn get_energized(tiles: &Tiles, starting_light: Light) -> BitGrid {
+    let mut occupied_tiles = BitGrid::new(tiles.width(), tiles.height());
+    occupied_tiles.insert(starting_light.pos.0, starting_light.pos.1);
 
-    let mut visited = HashSet::new();
+    // One layer per direction, since beams crossing a tile in different directions differ
+    let mut visited = vec![BitGrid::new(tiles.width(), tiles.height()); 4];
 
     let mut to_visit = VecDeque::new();
     to_visit.push_back(starting_light);
 
-    while !to_visit.is_empty() {
-        let light = to_visit.pop_front().unwrap();
-
-        if visited.contains(&light) {
+    while let Some(light) = to_visit.pop_front() {
+        let (x, y) = light.pos;
+        if !visited[light.direction as usize].insert(x, y) {
             continue;
         }
-        visited.insert(light);
 
         follow_light_beam_once(tiles, light)
             .into_iter()
             .for_each(|light| {
                 to_visit.push_back(light);
-                occupied_tiles.insert(light.pos);
+                occupied_tiles.insert(light.pos.0, light.pos.1);
             });
     }
 
     occupied_tiles
 }
 
+pub fn render(dir: &Path) -> anyhow::Result<()> {
+    let tiles: Tiles = INPUT.parse().unwrap();
+    let energized = get_energized(
+        &tiles,
+        Light {
+            pos: (0, 0),
+            direction: Right,
+        },
+    );
+
+    let grid = Grid::from_fn(tiles.width(), tiles.height(), |x, y| {
+        match (tiles.get(x, y), energized.get(x, y)) {
+            ('.', true) => render::YELLOW,
+            ('.', false) => render::BLACK,
+            (_, true) => render::WHITE,
+            (_, false) => render::GREY,
+        }
+    });
+
+    std::fs::create_dir_all(dir)?;
+    Image::from_grid(&grid, 4, |&color| color).write(&dir.join("day16_energized.png"))
+}
+
 pub fn pt1() {
     let tiles: Tiles = INPUT.parse().unwrap();
 
@@ -211,7 +237,7 @@ pub fn pt1() {
         pos: (0, 0),
         direction: Right,
     };
-    let result: usize = get_energized(&tiles, starter_light).into_iter().count();
+    let result: usize = get_energized(&tiles, starter_light).count_ones();
 
     println!("{}", result);
 }
@@ -229,8 +255,7 @@ pub fn pt2() {
                     direction: Down,
                 },
             )
-            .into_iter()
-            .count(),
+            .count_ones(),
         );
 
         energized.push(
@@ -241,8 +266,7 @@ pub fn pt2() {
                     direction: Up,
                 },
             )
-            .into_iter()
-            .count(),
+            .count_ones(),
         );
     }
 
@@ -255,8 +279,7 @@ pub fn pt2() {
                     direction: Right,
                 },
             )
-            .into_iter()
-            .count(),
+            .count_ones(),
         );
 
         energized.push(
@@ -267,8 +290,7 @@ pub fn pt2() {
                     direction: Left,
                 },
             )
-            .into_iter()
-            .count(),
+            .count_ones(),
         );
     }
 
@@ -1,6 +1,9 @@
+use aoc_2023::grid::Grid;
+use aoc_2023::render::{self, Image};
 use std::cmp::Reverse;
 use std::collections::{BinaryHeap, HashMap, HashSet};
 use std::convert::Infallible;
+use std::path::Path;
 use std::str::FromStr;
 
 const INPUT: &str = include_str!("input.dat");
@@ -48,12 +51,36 @@ enum Direction {
 }
 use Direction::*;
 
+// A position together with the direction a ship was heading when it got there
+type State = ((usize, usize), Direction);
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 struct Ship {
     direction: Direction,
     num_straight: u8,
     pos: (usize, usize),
     heat_loss: u64,
+    turned_at: Option<State>,
+}
+
+struct Route {
+    heat_loss: u64,
+    turns: Vec<(usize, usize)>,
+}
+
+impl Route {
+    fn tiles(&self) -> HashSet<(usize, usize)> {
+        let mut tiles = HashSet::new();
+        for pair in self.turns.windows(2) {
+            let ((x1, y1), (x2, y2)) = (pair[0], pair[1]);
+            for x in x1.min(x2)..=x1.max(x2) {
+                for y in y1.min(y2)..=y1.max(y2) {
+                    tiles.insert((x, y));
+                }
+            }
+        }
+        tiles
+    }
 }
 
 impl PartialOrd for Ship {
@@ -89,9 +116,10 @@ fn find_minimal_route(
     ships: Vec<Ship>,
     min_straight: u8,
     max_straight: u8,
-) -> u64 {
+) -> Route {
     let mut queue: BinaryHeap<_> = ships.into_iter().collect();
-    let mut visited: HashMap<((usize, usize), Direction), u64> = HashMap::new();
+    let mut visited: HashMap<State, u64> = HashMap::new();
+    let mut turned_at: HashMap<State, State> = HashMap::new();
 
     while !queue.is_empty() {
         let mut ship = queue.pop().unwrap();
@@ -103,11 +131,27 @@ fn find_minimal_route(
         }
 
         visited.insert((ship.pos, ship.direction), ship.heat_loss);
+        if let Some(prev) = ship.turned_at {
+            turned_at.insert((ship.pos, ship.direction), prev);
+        }
 
         if ship.pos == (heatmap.width() - 1, heatmap.height() - 1) {
-            return ship.heat_loss;
+            let mut turns = vec![ship.pos];
+            let mut state = (ship.pos, ship.direction);
+            while let Some(&prev) = turned_at.get(&state) {
+                turns.push(prev.0);
+                state = prev;
+            }
+            turns.reverse();
+
+            return Route {
+                heat_loss: ship.heat_loss,
+                turns,
+            };
         }
 
+        let turn_start = (ship.pos, ship.direction);
+
         let mut directions = HashSet::new();
         match ship.direction {
             Up | Down => {
@@ -135,6 +179,7 @@ fn find_minimal_route(
                     let mut new_ship = ship.clone();
                     new_ship.direction = *direction;
                     new_ship.num_straight = 0;
+                    new_ship.turned_at = Some(turn_start);
 
                     queue.push(new_ship);
                 }
@@ -145,44 +190,77 @@ fn find_minimal_route(
     unreachable!()
 }
 
-pub fn pt1() {
-    let heatmap: HeatMap = INPUT.parse().unwrap();
-    let result = find_minimal_route(
-        &heatmap,
+fn pt1_route(heatmap: &HeatMap) -> Route {
+    find_minimal_route(
+        heatmap,
         vec![Ship {
             direction: Down,
             num_straight: 0,
             pos: (0, 0),
             heat_loss: 0,
+            turned_at: None,
         }],
         0,
         3,
-    );
-
-    println!("{}", result);
+    )
 }
 
-pub fn pt2() {
-    let heatmap: HeatMap = INPUT.parse().unwrap();
-    let result = find_minimal_route(
-        &heatmap,
+fn pt2_route(heatmap: &HeatMap) -> Route {
+    find_minimal_route(
+        heatmap,
         vec![
             Ship {
                 direction: Right,
                 num_straight: 0,
                 pos: (0, 0),
                 heat_loss: 0,
+                turned_at: None,
             },
             Ship {
                 direction: Down,
                 num_straight: 0,
                 pos: (0, 0),
                 heat_loss: 0,
+                turned_at: None,
             },
         ],
         4,
         10,
-    );
+    )
+}
+
+pub fn render(dir: &Path) -> anyhow::Result<()> {
+    let heatmap: HeatMap = INPUT.parse().unwrap();
+    std::fs::create_dir_all(dir)?;
+
+    for (name, route) in [("pt1", pt1_route(&heatmap)), ("pt2", pt2_route(&heatmap))] {
+        let path = route.tiles();
+        let grid = Grid::from_fn(heatmap.width(), heatmap.height(), |x, y| {
+            if path.contains(&(x, y)) {
+                render::RED
+            } else {
+                let shade = heatmap.get(x, y) * 25;
+                [shade, shade, shade]
+            }
+        });
+
+        let file = dir.join(format!("day17_{}_route.png", name));
+        Image::from_grid(&grid, 4, |&color| color).write(&file)?;
+    }
+
+    Ok(())
+}
+
+pub fn pt1() {
+    let heatmap: HeatMap = INPUT.parse().unwrap();
+    let result = pt1_route(&heatmap);
+
+    println!("{}", result.heat_loss);
+}
+
+pub fn pt2() {
+    let heatmap: HeatMap = INPUT.parse().unwrap();
+    let result = pt2_route(&heatmap);
 
-    println!("{}", result);
+    println!("{}", result.heat_loss);
 }
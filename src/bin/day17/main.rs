@@ -1,8 +1,14 @@
+use aoc_2023::render::render_dir_from_args;
 use std::time::Instant;
 
 mod bothparts;
 
 fn main() {
+    if let Some(dir) = render_dir_from_args() {
+        bothparts::render(&dir).unwrap();
+        return;
+    }
+
     let start = Instant::now();
     println!("Part 1:");
     bothparts::pt1();
@@ -1,9 +1,15 @@
+use aoc_2023::render::render_dir_from_args;
 use std::time::Instant;
 
 mod pt1;
 mod pt2;
 
 fn main() {
+    if let Some(dir) = render_dir_from_args() {
+        pt1::render(&dir).unwrap();
+        return;
+    }
+
     let start = Instant::now();
     println!("Part 1:");
     pt1::pt1();
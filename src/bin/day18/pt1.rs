@@ -1,8 +1,12 @@
 use super::*;
+use aoc_2023::geometry::Polygon;
+use aoc_2023::grid;
+use aoc_2023::render::{self, Image, Rgb};
 use lazy_static::lazy_static;
 use regex::Regex;
 use std::collections::{HashMap, HashSet, VecDeque};
 use std::convert::Infallible;
+use std::path::Path;
 use std::str::FromStr;
 
 const INPUT: &str = include_str!("input.dat");
@@ -10,20 +14,17 @@ const INPUT: &str = include_str!("input.dat");
 const SAMPLE: &str = include_str!("sample.dat");
 
 struct Grid {
-    vertices: Vec<(i64, i64)>,
-    bound_size: u64,
+    trench: Polygon,
 }
 
 impl Grid {
     fn from_instrs(instrs: &[Instr]) -> Self {
         let mut vertices = Vec::new();
         vertices.push((0, 0));
-        let mut bound_size = 0;
 
         let mut cx = 0;
         let mut cy = 0;
         for instr in instrs {
-            bound_size += instr.dist;
             let (dx, dy) = match instr.dir {
                 Up => (0, -(instr.dist as i64)),
                 Down => (0, instr.dist as i64),
@@ -37,28 +38,12 @@ impl Grid {
             vertices.push((cx, cy));
         }
         Grid {
-            vertices,
-            bound_size,
+            trench: Polygon::new(vertices),
         }
     }
 
     fn area(&self) -> u64 {
-        let sum: u64 = (0..self.vertices.len())
-            .map(|i| {
-                let p1 = (i + 1) % self.vertices.len();
-
-                let lhx = self.vertices[i].0;
-                let lhy = self.vertices[p1].1;
-
-                let rhx = self.vertices[p1].0;
-                let rhy = self.vertices[i].1;
-
-                (lhx * lhy) - (rhx * rhy)
-            })
-            .sum::<i64>() as u64;
-        let shoelace = sum / 2;
-
-        shoelace + (self.bound_size / 2) + 1
+        self.trench.lattice_points()
     }
 }
 
@@ -103,6 +88,46 @@ impl FromStr for Instr {
     }
 }
 
+fn parse_color(color: &str) -> Rgb {
+    let rgb = u32::from_str_radix(color.trim_start_matches('#'), 16).unwrap();
+    [(rgb >> 16) as u8, (rgb >> 8) as u8, rgb as u8]
+}
+
+// Draws the trench outline in the colour each instruction paints it.
+pub fn render(dir: &Path) -> anyhow::Result<()> {
+    let instrs: Vec<Instr> = INPUT.lines().map(|l| l.parse().unwrap()).collect();
+
+    let mut trench = Vec::new();
+    let (mut cx, mut cy) = (0i64, 0i64);
+    for instr in &instrs {
+        let color = parse_color(&instr.color);
+        for _ in 0..instr.dist {
+            match instr.dir {
+                Up => cy -= 1,
+                Down => cy += 1,
+                Left => cx -= 1,
+                Right => cx += 1,
+            }
+            trench.push(((cx, cy), color));
+        }
+    }
+
+    let min_x = trench.iter().map(|((x, _), _)| *x).min().unwrap_or(0);
+    let min_y = trench.iter().map(|((_, y), _)| *y).min().unwrap_or(0);
+    let max_x = trench.iter().map(|((x, _), _)| *x).max().unwrap_or(0);
+    let max_y = trench.iter().map(|((_, y), _)| *y).max().unwrap_or(0);
+
+    let width = (max_x - min_x + 1) as usize;
+    let height = (max_y - min_y + 1) as usize;
+    let mut grid = grid::Grid::new(width, height, render::BLACK);
+    for ((x, y), color) in trench {
+        grid.set((x - min_x) as usize, (y - min_y) as usize, color);
+    }
+
+    std::fs::create_dir_all(dir)?;
+    Image::from_grid(&grid, 2, |&color| color).write(&dir.join("day18_trench.png"))
+}
+
 pub fn pt1() {
     let instrs: Vec<Instr> = INPUT.lines().map(|l| l.parse().unwrap()).collect();
     let grid = Grid::from_instrs(&instrs);
@@ -1,3 +1,4 @@
+use aoc_2023::geometry::Polygon;
 use std::collections::{HashMap, HashSet, VecDeque};
 use std::convert::Infallible;
 use std::str::FromStr;
@@ -7,20 +8,17 @@ const INPUT: &str = include_str!("input.dat");
 const SAMPLE: &str = include_str!("sample.dat");
 
 struct Grid {
-    vertices: Vec<(i64, i64)>,
-    bound_size: u64,
+    trench: Polygon,
 }
 
 impl Grid {
     fn from_instrs(instrs: &[Instr]) -> Self {
         let mut vertices = Vec::new();
         vertices.push((0, 0));
-        let mut bound_size = 0;
 
         let mut cx = 0;
         let mut cy = 0;
         for instr in instrs {
-            bound_size += instr.dist;
             let (dx, dy) = match instr.dir {
                 Up => (0, -(instr.dist as i64)),
                 Down => (0, instr.dist as i64),
@@ -34,28 +32,12 @@ impl Grid {
             vertices.push((cx, cy));
         }
         Grid {
-            vertices,
-            bound_size,
+            trench: Polygon::new(vertices),
         }
     }
 
     fn area(&self) -> u64 {
-        let sum: u64 = (0..self.vertices.len())
-            .map(|i| {
-                let p1 = (i + 1) % self.vertices.len();
-
-                let lhx = self.vertices[i].0;
-                let lhy = self.vertices[p1].1;
-
-                let rhx = self.vertices[p1].0;
-                let rhy = self.vertices[i].1;
-
-                (lhx * lhy) - (rhx * rhy)
-            })
-            .sum::<i64>() as u64;
-        let shoelace = sum / 2;
-
-        shoelace + (self.bound_size / 2) + 1
+        self.trench.lattice_points()
     }
 }
 
@@ -1,5 +1,8 @@
-use std::collections::HashMap;
-use std::convert::Infallible;
+use anyhow::{anyhow, bail};
+use aoc_2023::graph::Graph;
+use aoc_2023::intern::Interner;
+use aoc_2023::parse;
+use itertools::Itertools;
 use std::str::FromStr;
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
@@ -11,26 +14,25 @@ struct Part {
 }
 
 impl FromStr for Part {
-    type Err = Infallible;
+    type Err = anyhow::Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let s = s.trim_start_matches('{').trim_end_matches('}');
-        let mut attr_split = s.split(',');
+        let attrs = s.trim_start_matches('{').trim_end_matches('}');
+        let mut attr_split = attrs.split(',');
 
-        let mut parse_next = || {
-            attr_split
+        let mut parse_next = || -> anyhow::Result<u64> {
+            let attr = attr_split
                 .next()
-                .unwrap()
-                .split('=')
-                .last()
-                .unwrap()
-                .parse::<u64>()
-                .unwrap()
+                .ok_or_else(|| anyhow!("expected four ratings in '{}'", s))?;
+            let (_, val) = attr
+                .split_once('=')
+                .ok_or_else(|| anyhow!("expected 'name=value', got '{}'", attr))?;
+            Ok(val.parse()?)
         };
-        let x = parse_next();
-        let m = parse_next();
-        let a = parse_next();
-        let s = parse_next();
+        let x = parse_next()?;
+        let m = parse_next()?;
+        let a = parse_next()?;
+        let s = parse_next()?;
 
         Ok(Part { x, m, a, s })
     }
@@ -38,7 +40,7 @@ impl FromStr for Part {
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 struct Workflow {
-    name: String,
+    id: usize,
     rules: Vec<Rule>,
 }
 
@@ -54,25 +56,72 @@ impl Workflow {
     }
 }
 
-impl FromStr for Workflow {
-    type Err = Infallible;
+impl Workflow {
+    fn parse(s: &str, names: &mut Interner) -> anyhow::Result<Workflow> {
+        let (name, rule_str) = s
+            .trim_end_matches('}')
+            .split_once('{')
+            .ok_or_else(|| anyhow!("expected 'name{{rules}}', got '{}'", s))?;
+
+        let id = names.intern(name);
+        let rules = rule_str
+            .split(',')
+            .map(|r| Rule::parse(r, names))
+            .collect::<anyhow::Result<_>>()?;
+
+        Ok(Workflow { id, rules })
+    }
+}
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let s = s.trim_end_matches('}');
-        let mut part_iter = s.split('{');
+// Workflows indexed by their interned name.
+#[derive(Debug, Clone)]
+struct Workflows {
+    names: Interner,
+    flows: Vec<Workflow>,
+    start: usize,
+}
 
-        let name = part_iter.next().unwrap().to_string();
+impl Workflows {
+    fn parse(s: &str) -> anyhow::Result<Workflows> {
+        let mut names = Interner::new();
+        let mut flows: Vec<Workflow> = s
+            .lines()
+            .map(|l| Workflow::parse(l, &mut names))
+            .collect::<anyhow::Result<_>>()?;
+        flows.sort_by_key(|w| w.id);
+
+        if let Some(pair) = flows.windows(2).find(|pair| pair[0].id == pair[1].id) {
+            bail!(
+                "workflow '{}' is defined more than once",
+                names.name(pair[0].id)
+            );
+        }
+        if let Some(id) = (0..names.len()).find(|&id| flows.get(id).map(|w| w.id) != Some(id)) {
+            bail!("workflow '{}' is never defined", names.name(id));
+        }
+        let start = names
+            .get("in")
+            .ok_or_else(|| anyhow!("no 'in' workflow to start from"))?;
+
+        Ok(Workflows {
+            names,
+            flows,
+            start,
+        })
+    }
 
-        let rule_str = part_iter.next().unwrap();
-        let rules = rule_str.split(',').map(|r| r.parse().unwrap()).collect();
+    fn get(&self, id: usize) -> &Workflow {
+        &self.flows[id]
+    }
 
-        Ok(Workflow { name, rules })
+    fn start(&self) -> &Workflow {
+        self.get(self.start)
     }
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 enum RuleResult {
-    GoTo { name: String },
+    GoTo { id: usize },
     Accepted,
     Rejected,
 }
@@ -87,13 +136,18 @@ struct Condition {
 }
 
 impl FromStr for Condition {
-    type Err = Infallible;
+    type Err = anyhow::Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let oper = if s.contains('<') { '<' } else { '>' };
-        let mut part_iter = s.split(oper);
-        let attr = part_iter.next().unwrap().chars().next().unwrap();
-        let val = part_iter.next().unwrap().parse().unwrap();
+        let (attr, val) = s
+            .split_once(oper)
+            .ok_or_else(|| anyhow!("expected a '<' or '>' condition, got '{}'", s))?;
+        let attr = match attr {
+            "x" | "m" | "a" | "s" => attr.chars().next().unwrap(),
+            _ => bail!("unknown rating '{}' in '{}'", attr, s),
+        };
+        let val = val.parse()?;
 
         Ok(Condition { oper, attr, val })
     }
@@ -128,22 +182,18 @@ impl Rule {
     }
 }
 
-impl FromStr for Rule {
-    type Err = Infallible;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let parse_result = |s| match s {
+impl Rule {
+    fn parse(s: &str, names: &mut Interner) -> anyhow::Result<Rule> {
+        let mut parse_result = |s| match s {
             "R" => Rejected,
             "A" => Accepted,
             _ => GoTo {
-                name: s.to_string(),
+                id: names.intern(s),
             },
         };
-        if s.contains(':') {
-            let mut part_iter = s.split(':');
-
-            let condition = Some(part_iter.next().unwrap().parse().unwrap());
-            let result = parse_result(part_iter.next().unwrap());
+        if let Some((condition, result)) = s.split_once(':') {
+            let condition = Some(condition.parse()?);
+            let result = parse_result(result);
 
             Ok(Rule { condition, result })
         } else {
@@ -155,34 +205,54 @@ impl FromStr for Rule {
     }
 }
 
-fn parse_inp(input: &str) -> (HashMap<String, Workflow>, Vec<Part>) {
-    let mut overall_iter = input.split("\n\n");
+fn parse_inp(input: &str) -> anyhow::Result<(Workflows, Vec<Part>)> {
+    let mut overall_iter = parse::blocks(input);
 
-    let workflow_part = overall_iter.next().unwrap();
-    let part_part = overall_iter.next().unwrap();
+    let workflow_part = overall_iter
+        .next()
+        .ok_or_else(|| anyhow!("missing workflows"))?;
+    let part_part = overall_iter
+        .next()
+        .ok_or_else(|| anyhow!("missing parts"))?;
 
-    let workflows = workflow_part
+    let workflows = Workflows::parse(workflow_part)?;
+
+    let parts = part_part
         .lines()
-        .map(|i| {
-            let workflow: Workflow = i.parse().unwrap();
-            (workflow.name.clone(), workflow)
-        })
-        .collect();
+        .map(str::parse)
+        .collect::<anyhow::Result<_>>()?;
 
-    let parts = part_part.lines().map(|l| l.parse().unwrap()).collect();
+    Ok((workflows, parts))
+}
 
-    (workflows, parts)
+fn to_graph(workflows: &Workflows) -> Graph {
+    let mut graph = Graph::new();
+    let names = &workflows.names;
+    for workflow in workflows.flows.iter().sorted_by_key(|w| names.name(w.id)) {
+        for rule in &workflow.rules {
+            let to = match rule.result {
+                GoTo { id } => names.name(id),
+                Accepted => "A",
+                Rejected => "R",
+            };
+            let label = rule
+                .condition
+                .map(|c| format!("{}{}{}", c.attr, c.oper, c.val));
+            graph.add_edge(names.name(workflow.id), to, label.as_deref());
+        }
+    }
+    graph
 }
 
 const INPUT: &str = include_str!("input.dat");
 #[allow(dead_code)]
 const SAMPLE: &str = include_str!("sample.dat");
 
-fn find_accepted(workflows: &HashMap<String, Workflow>, parts: &[Part]) -> Vec<Part> {
+fn find_accepted(workflows: &Workflows, parts: &[Part]) -> Vec<Part> {
     let mut accepted = Vec::with_capacity(parts.len());
 
     for part in parts {
-        let mut workflow_result = workflows["in"].evaluate(part);
+        let mut workflow_result = workflows.start().evaluate(part);
 
         loop {
             match workflow_result {
@@ -193,7 +263,7 @@ fn find_accepted(workflows: &HashMap<String, Workflow>, parts: &[Part]) -> Vec<P
                 Rejected => {
                     break;
                 }
-                GoTo { name } => workflow_result = workflows[name].evaluate(part),
+                GoTo { id } => workflow_result = workflows.get(*id).evaluate(part),
             }
         }
     }
@@ -201,8 +271,13 @@ fn find_accepted(workflows: &HashMap<String, Workflow>, parts: &[Part]) -> Vec<P
     accepted
 }
 
+pub fn dot() {
+    let (workflows, _) = parse_inp(INPUT).unwrap();
+    print!("{}", to_graph(&workflows).to_dot());
+}
+
 pub fn pt1() {
-    let (workflows, parts) = parse_inp(INPUT);
+    let (workflows, parts) = parse_inp(INPUT).unwrap();
 
     let accepted = find_accepted(&workflows, &parts);
 
@@ -1,32 +1,76 @@
-use std::collections::HashMap;
-use std::convert::Infallible;
+use anyhow::{anyhow, bail};
+use aoc_2023::intern::Interner;
+use aoc_2023::interval::{count_disjoint, Cuboid, Interval};
+use aoc_2023::parse;
 use std::str::FromStr;
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 struct Workflow {
-    name: String,
+    id: usize,
     rules: Vec<Rule>,
 }
 
-impl FromStr for Workflow {
-    type Err = Infallible;
+impl Workflow {
+    fn parse(s: &str, names: &mut Interner) -> anyhow::Result<Workflow> {
+        let (name, rule_str) = s
+            .trim_end_matches('}')
+            .split_once('{')
+            .ok_or_else(|| anyhow!("expected 'name{{rules}}', got '{}'", s))?;
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let s = s.trim_end_matches('}');
-        let mut part_iter = s.split('{');
+        let id = names.intern(name);
+        let rules = rule_str
+            .split(',')
+            .map(|r| Rule::parse(r, names))
+            .collect::<anyhow::Result<_>>()?;
 
-        let name = part_iter.next().unwrap().to_string();
+        Ok(Workflow { id, rules })
+    }
+}
+
+// Workflows indexed by their interned name.
+#[derive(Debug, Clone)]
+struct Workflows {
+    flows: Vec<Workflow>,
+    start: usize,
+}
 
-        let rule_str = part_iter.next().unwrap();
-        let rules = rule_str.split(',').map(|r| r.parse().unwrap()).collect();
+impl Workflows {
+    fn parse(s: &str) -> anyhow::Result<Workflows> {
+        let mut names = Interner::new();
+        let mut flows: Vec<Workflow> = s
+            .lines()
+            .map(|l| Workflow::parse(l, &mut names))
+            .collect::<anyhow::Result<_>>()?;
+        flows.sort_by_key(|w| w.id);
+
+        if let Some(pair) = flows.windows(2).find(|pair| pair[0].id == pair[1].id) {
+            bail!(
+                "workflow '{}' is defined more than once",
+                names.name(pair[0].id)
+            );
+        }
+        if let Some(id) = (0..names.len()).find(|&id| flows.get(id).map(|w| w.id) != Some(id)) {
+            bail!("workflow '{}' is never defined", names.name(id));
+        }
+        let start = names
+            .get("in")
+            .ok_or_else(|| anyhow!("no 'in' workflow to start from"))?;
+
+        Ok(Workflows { flows, start })
+    }
 
-        Ok(Workflow { name, rules })
+    fn get(&self, id: usize) -> &Workflow {
+        &self.flows[id]
+    }
+
+    fn start(&self) -> &Workflow {
+        self.get(self.start)
     }
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 enum RuleResult {
-    GoTo { name: String },
+    GoTo { id: usize },
     Accepted,
     Rejected,
 }
@@ -56,13 +100,18 @@ impl Condition {
 }
 
 impl FromStr for Condition {
-    type Err = Infallible;
+    type Err = anyhow::Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let oper = if s.contains('<') { '<' } else { '>' };
-        let mut part_iter = s.split(oper);
-        let attr = part_iter.next().unwrap().chars().next().unwrap();
-        let val = part_iter.next().unwrap().parse().unwrap();
+        let (attr, val) = s
+            .split_once(oper)
+            .ok_or_else(|| anyhow!("expected a '<' or '>' condition, got '{}'", s))?;
+        let attr = match attr {
+            "x" | "m" | "a" | "s" => attr.chars().next().unwrap(),
+            _ => bail!("unknown rating '{}' in '{}'", attr, s),
+        };
+        let val = val.parse()?;
 
         Ok(Condition { oper, attr, val })
     }
@@ -74,22 +123,18 @@ struct Rule {
     result: RuleResult,
 }
 
-impl FromStr for Rule {
-    type Err = Infallible;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let parse_result = |s| match s {
+impl Rule {
+    fn parse(s: &str, names: &mut Interner) -> anyhow::Result<Rule> {
+        let mut parse_result = |s| match s {
             "R" => Rejected,
             "A" => Accepted,
             _ => GoTo {
-                name: s.to_string(),
+                id: names.intern(s),
             },
         };
-        if s.contains(':') {
-            let mut part_iter = s.split(':');
-
-            let condition = Some(part_iter.next().unwrap().parse().unwrap());
-            let result = parse_result(part_iter.next().unwrap());
+        if let Some((condition, result)) = s.split_once(':') {
+            let condition = Some(condition.parse()?);
+            let result = parse_result(result);
 
             Ok(Rule { condition, result })
         } else {
@@ -101,26 +146,20 @@ impl FromStr for Rule {
     }
 }
 
-fn parse_inp(input: &str) -> HashMap<String, Workflow> {
-    let mut overall_iter = input.split("\n\n");
+fn parse_inp(input: &str) -> anyhow::Result<Workflows> {
+    let mut overall_iter = parse::blocks(input);
 
-    let workflow_part = overall_iter.next().unwrap();
+    let workflow_part = overall_iter
+        .next()
+        .ok_or_else(|| anyhow!("missing workflows"))?;
 
-    let workflows = workflow_part
-        .lines()
-        .map(|i| {
-            let workflow: Workflow = i.parse().unwrap();
-            (workflow.name.clone(), workflow)
-        })
-        .collect();
-
-    workflows
+    Workflows::parse(workflow_part)
 }
 
 fn all_paths(
     mut curr_rules: Vec<Condition>,
     workflow: &Workflow,
-    workflows: &HashMap<String, Workflow>,
+    workflows: &Workflows,
 ) -> Vec<Vec<Condition>> {
     let mut results = vec![];
 
@@ -133,16 +172,16 @@ fn all_paths(
                 match &rule.result {
                     Accepted => results.push(poss_rules),
                     Rejected => continue,
-                    GoTo { name } => {
-                        results.append(&mut all_paths(poss_rules, &workflows[name], workflows))
+                    GoTo { id } => {
+                        results.append(&mut all_paths(poss_rules, workflows.get(*id), workflows))
                     }
                 }
             }
             None => match &rule.result {
                 Accepted => results.push(poss_rules),
                 Rejected => break,
-                GoTo { name } => {
-                    results.append(&mut all_paths(poss_rules, &workflows[name], workflows))
+                GoTo { id } => {
+                    results.append(&mut all_paths(poss_rules, workflows.get(*id), workflows))
                 }
             },
         }
@@ -151,50 +190,28 @@ fn all_paths(
     results
 }
 
-#[derive(Debug, Copy, Clone)]
-struct MinMax {
-    x: (u32, u32),
-    m: (u32, u32),
-    a: (u32, u32),
-    s: (u32, u32),
-}
-
-impl MinMax {
-    fn new() -> MinMax {
-        let x = (1, 4001);
-        let m = (1, 4001);
-        let a = (1, 4001);
-        let s = (1, 4001);
-
-        MinMax { x, m, a, s }
-    }
-
-    fn constrain(&mut self, condition: Condition) {
-        let range = match condition.attr {
-            'x' => &mut self.x,
-            'm' => &mut self.m,
-            'a' => &mut self.a,
-            's' => &mut self.s,
-            _ => panic!(),
-        };
+type Ratings = Cuboid<u32, 4>;
 
-        match condition.oper {
-            '<' => {
-                range.1 = u32::min(range.1, condition.val as u32);
-            }
-            '>' => {
-                range.0 = u32::max(range.0, (condition.val + 1) as u32);
-            }
-            _ => panic!(),
-        }
-    }
+fn all_ratings() -> Ratings {
+    Cuboid::new([Interval::new(1, 4001); 4])
+}
 
-    fn cardinality(&self) -> u64 {
-        (self.x.1 - self.x.0) as u64
-            * (self.m.1 - self.m.0) as u64
-            * (self.a.1 - self.a.0) as u64
-            * (self.s.1 - self.s.0) as u64
-    }
+fn constrain(ratings: &Ratings, condition: Condition) -> Option<Ratings> {
+    let dim = match condition.attr {
+        'x' => 0,
+        'm' => 1,
+        'a' => 2,
+        's' => 3,
+        _ => panic!(),
+    };
+
+    let allowed = match condition.oper {
+        '<' => Interval::new(0, condition.val as u32),
+        '>' => Interval::new((condition.val + 1) as u32, u32::MAX),
+        _ => panic!(),
+    };
+
+    ratings.constrain(dim, &allowed)
 }
 
 const INPUT: &str = include_str!("input.dat");
@@ -202,21 +219,22 @@ const INPUT: &str = include_str!("input.dat");
 const SAMPLE: &str = include_str!("sample.dat");
 
 pub fn pt2() {
-    let workflows = parse_inp(INPUT);
+    let workflows = parse_inp(INPUT).unwrap();
 
-    let in_wkflow = &workflows["in"];
+    let in_wkflow = workflows.start();
     let all_paths = all_paths(vec![], in_wkflow, &workflows);
 
-    let result: u64 = all_paths
+    let accepted: Vec<Ratings> = all_paths
         .iter()
-        .map(|condition_list| {
-            let mut min_max = MinMax::new();
+        .filter_map(|condition_list| {
             condition_list
                 .iter()
-                .for_each(|condition| min_max.constrain(*condition));
-            min_max.cardinality()
+                .try_fold(all_ratings(), |ratings, condition| {
+                    constrain(&ratings, *condition)
+                })
         })
-        .sum();
+        .collect();
+    let result = count_disjoint(&accepted);
 
     println!("{}", result);
 }
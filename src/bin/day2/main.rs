@@ -1,70 +1,106 @@
-use lazy_static::lazy_static;
-use regex::Regex;
-use std::convert::Infallible;
+use anyhow::anyhow;
+use itertools::Itertools;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
 use std::str::FromStr;
 
-type Result<T> = std::result::Result<T, Infallible>;
 const INPUT: &str = include_str!("input.dat");
 
-lazy_static! {
-    static ref RED_RE: Regex = Regex::new(r"(\d)* red").unwrap();
-    static ref GREEN_RE: Regex = Regex::new(r"(\d)* green").unwrap();
-    static ref BLUE_RE: Regex = Regex::new(r"(\d)* blue").unwrap();
+// Cube counts by colour, used both for a single grab and for the contents of a bag.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+struct Cubes {
+    counts: BTreeMap<String, u32>,
 }
 
-#[derive(Debug, Clone)]
-struct Game {
-    game_id: u32,
-    grabs: Vec<Grab>,
-}
+impl Cubes {
+    fn get(&self, colour: &str) -> u32 {
+        self.counts.get(colour).copied().unwrap_or_default()
+    }
 
-impl FromStr for Game {
-    type Err = Infallible;
+    fn colours(&self) -> impl Iterator<Item = &str> {
+        self.counts.keys().map(String::as_str)
+    }
 
-    fn from_str(s: &str) -> Result<Self> {
-        let mut game_sep = s.split(':');
-        let game_id_str = game_sep.next().unwrap();
+    fn fits_in(&self, bag: &Cubes) -> bool {
+        self.counts
+            .iter()
+            .all(|(colour, &count)| count <= bag.get(colour))
+    }
 
-        let game_id = game_id_str.split(' ').last().unwrap().parse().unwrap();
+    fn max(mut self, other: &Cubes) -> Cubes {
+        for (colour, &count) in other.counts.iter() {
+            let entry = self.counts.entry(colour.clone()).or_default();
+            *entry = (*entry).max(count);
+        }
+        self
+    }
 
-        let grabs_str = game_sep.next().unwrap();
-        let grabs = grabs_str
-            .split(';')
-            .map(Grab::from_str)
-            .collect::<Result<Vec<_>>>()
-            .unwrap();
+    fn power<'a>(&self, colours: impl IntoIterator<Item = &'a str>) -> u32 {
+        colours.into_iter().map(|colour| self.get(colour)).product()
+    }
+}
 
-        Ok(Game { game_id, grabs })
+// Parses "3 blue, 4 red"
+impl FromStr for Cubes {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut cubes = Cubes::default();
+        for part in s.split(',').map(str::trim).filter(|part| !part.is_empty()) {
+            let (count, colour) = part
+                .split_once(' ')
+                .ok_or_else(|| anyhow!("expected 'count colour', got '{}'", part))?;
+            *cubes.counts.entry(colour.trim().to_string()).or_default() += count.parse::<u32>()?;
+        }
+        Ok(cubes)
     }
 }
 
-#[derive(Debug, Copy, Clone)]
-struct Grab {
-    red: u32,
-    green: u32,
-    blue: u32,
+impl fmt::Display for Cubes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let parts = self
+            .counts
+            .iter()
+            .map(|(colour, count)| format!("{} {}", count, colour));
+        write!(f, "{}", parts.format(", "))
+    }
 }
 
-impl FromStr for Grab {
-    type Err = Infallible;
+#[derive(Debug, Clone)]
+struct Game {
+    game_id: u32,
+    grabs: Vec<Cubes>,
+}
 
-    fn from_str(s: &str) -> Result<Self> {
-        let red = RED_RE
-            .find(s)
-            .and_then(|m| m.as_str().split(' ').next().map(|s| s.parse().unwrap()))
-            .unwrap_or_default();
+impl Game {
+    fn is_possible_with(&self, bag: &Cubes) -> bool {
+        self.grabs.iter().all(|grab| grab.fits_in(bag))
+    }
 
-        let green = GREEN_RE
-            .find(s)
-            .and_then(|m| m.as_str().split(' ').next().map(|s| s.parse().unwrap()))
-            .unwrap_or_default();
+    fn minimum_bag(&self) -> Cubes {
+        self.grabs.iter().fold(Cubes::default(), Cubes::max)
+    }
+}
 
-        let blue = BLUE_RE
-            .find(s)
-            .and_then(|m| m.as_str().split(' ').next().map(|s| s.parse().unwrap()))
-            .unwrap_or_default();
+impl FromStr for Game {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (game_id_str, grabs_str) = s
+            .split_once(':')
+            .ok_or_else(|| anyhow!("expected 'Game N: ...', got '{}'", s))?;
+
+        let game_id = game_id_str
+            .rsplit(' ')
+            .next()
+            .ok_or_else(|| anyhow!("missing game id in '{}'", s))?
+            .parse()?;
+        let grabs = grabs_str
+            .split(';')
+            .map(str::parse)
+            .collect::<anyhow::Result<_>>()?;
 
-        Ok(Grab { red, green, blue })
+        Ok(Game { game_id, grabs })
     }
 }
 
@@ -72,32 +108,40 @@ fn games() -> Vec<Game> {
     INPUT
         .lines()
         .map(str::parse)
-        .collect::<Result<_>>()
+        .collect::<anyhow::Result<_>>()
         .unwrap()
 }
 
+fn feasible_games<'a>(games: &'a [Game], bag: &'a Cubes) -> impl Iterator<Item = &'a Game> {
+    games.iter().filter(move |game| game.is_possible_with(bag))
+}
+
+// The bag from `--bag "12 red, 13 green, 14 blue"`, if given
+fn bag_from_args() -> Option<anyhow::Result<Cubes>> {
+    let mut args = std::env::args().skip_while(|arg| arg != "--bag");
+    args.next()?;
+    Some(
+        args.next()
+            .ok_or_else(|| anyhow!("--bag needs a bag such as '12 red, 13 green'"))
+            .and_then(|spec| spec.parse()),
+    )
+}
+
 mod pt1 {
     use super::*;
 
-    const MAX_RED: u32 = 12;
-    const MAX_GREEN: u32 = 13;
-    const MAX_BLUE: u32 = 14;
+    const BAG: &str = "12 red, 13 green, 14 blue";
 
-    fn pt1_evaluate_game(game: &Game) -> bool {
-        for grab in game.grabs.iter() {
-            if grab.red > MAX_RED || grab.green > MAX_GREEN || grab.blue > MAX_BLUE {
-                return false;
-            }
-        }
-        true
+    pub fn query(bag: &Cubes) {
+        let games = games();
+        let ids = feasible_games(&games, bag).map(|game| game.game_id);
+        println!("Feasible with {}: {}", bag, ids.format(", "));
     }
 
     pub fn pt1() {
         let games = games();
-        let result: u32 = games
-            .iter()
-            .filter_map(|game| pt1_evaluate_game(game).then_some(game.game_id))
-            .sum();
+        let bag: Cubes = BAG.parse().unwrap();
+        let result: u32 = feasible_games(&games, &bag).map(|game| game.game_id).sum();
 
         println!("{}", result);
     }
@@ -106,37 +150,36 @@ mod pt1 {
 mod pt2 {
     use super::*;
 
-    impl Game {
-        fn power(&self) -> u32 {
-            let mut min_r = 0;
-            let mut min_g = 0;
-            let mut min_b = 0;
-
-            for grabs in self.grabs.iter() {
-                if grabs.red > min_r {
-                    min_r = grabs.red;
-                }
-                if grabs.green > min_g {
-                    min_g = grabs.green;
-                }
-                if grabs.blue > min_b {
-                    min_b = grabs.blue;
-                }
-            }
-
-            min_r * min_g * min_b
+    pub fn minimum_bags() {
+        for game in games() {
+            println!("Game {}: {}", game.game_id, game.minimum_bag());
         }
     }
 
     pub fn pt2() {
         let games = games();
-        let result: u32 = games.iter().map(|game| game.power()).sum();
+        let bags: Vec<Cubes> = games.iter().map(Game::minimum_bag).collect();
+        // A colour a game never shows still counts, as zero
+        let colours: BTreeSet<&str> = bags.iter().flat_map(Cubes::colours).collect();
+        let result: u32 = bags
+            .iter()
+            .map(|bag| bag.power(colours.iter().copied()))
+            .sum();
 
         println!("{}", result);
     }
 }
 
 fn main() {
+    if let Some(bag) = bag_from_args() {
+        pt1::query(&bag.unwrap());
+        return;
+    }
+    if std::env::args().any(|arg| arg == "--minimum") {
+        pt2::minimum_bags();
+        return;
+    }
+
     println!("Part 1:");
     pt1::pt1();
     println!("Part 2:");
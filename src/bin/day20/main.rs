@@ -4,6 +4,11 @@ mod pt1;
 mod pt2;
 
 fn main() {
+    if std::env::args().any(|arg| arg == "--dot") {
+        pt1::dot();
+        return;
+    }
+
     let start = Instant::now();
     println!("Part 1:");
     pt1::pt1();
@@ -1,8 +1,12 @@
 use super::*;
+use anyhow::anyhow;
+use aoc_2023::graph::Graph;
+use aoc_2023::intern::Interner;
+use aoc_2023::parse;
+use itertools::Itertools;
 use lazy_static::lazy_static;
 use regex::Regex;
-use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
-use std::convert::Infallible;
+use std::collections::{BTreeMap, VecDeque};
 use std::str::FromStr;
 
 const INPUT: &str = include_str!("input.dat");
@@ -19,8 +23,8 @@ use PulseKind::*;
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
 struct Pulse {
     kind: PulseKind,
-    from: String,
-    to: String,
+    from: usize,
+    to: usize,
 }
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
@@ -33,20 +37,17 @@ use ModuleKind::*;
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
 struct Module {
-    name: String,
+    name: usize,
     kind: ModuleKind,
-    connects_to: BTreeSet<String>,
-    conjunction: BTreeMap<String, PulseKind>,
+    connects_to: Vec<usize>,
+    conjunction: BTreeMap<usize, PulseKind>,
     flip_flop: bool,
 }
 
-impl FromStr for Module {
-    type Err = Infallible;
+impl Module {
+    fn parse(s: &str, names: &mut Interner) -> anyhow::Result<Module> {
+        let (mod_id, targets) = parse::adjacency(s)?;
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut mod_parts = s.split("->");
-
-        let mod_id = mod_parts.next().unwrap();
         let (kind, name) = if mod_id.starts_with("broadcaster") {
             (Broadcaster, "broadcaster".to_string())
         } else if mod_id.starts_with('%') {
@@ -56,21 +57,22 @@ impl FromStr for Module {
             let name = mod_id.trim_start_matches('&').trim().to_string();
             (Conjunction, name)
         } else {
-            unreachable!();
+            return Err(anyhow!("unknown module kind in '{}'", s));
         };
 
-        let connects_to = mod_parts
-            .next()
-            .unwrap()
-            .split(',')
-            .map(|names| names.trim().to_string())
+        // Pulses go out in name order
+        let connects_to = targets
+            .into_iter()
+            .sorted()
+            .dedup()
+            .map(|target| names.intern(target))
             .collect();
 
         let conjunction = BTreeMap::new();
         let flip_flop = false;
 
         Ok(Module {
-            name,
+            name: names.intern(&name),
             kind,
             connects_to,
             conjunction,
@@ -81,18 +83,46 @@ impl FromStr for Module {
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 struct Coordinator {
-    modules: HashMap<String, Module>,
+    names: Interner,
+    modules: Vec<Option<Module>>,
+    button: usize,
+    broadcaster: usize,
+    rx: Option<usize>,
     low_pulses: u64,
     high_pulses: u64,
     done: bool,
 }
 
 impl Coordinator {
+    fn to_graph(&self) -> Graph {
+        let mut graph = Graph::new();
+        let modules = self
+            .modules
+            .iter()
+            .flatten()
+            .sorted_by_key(|m| self.names.name(m.name));
+        for module in modules {
+            let name = self.names.name(module.name);
+            let id = graph.add_node(name);
+            let indicator = match module.kind {
+                FlipFlop => "%",
+                Conjunction => "&",
+                Broadcaster => "",
+            };
+            graph.set_label(id, &format!("{}{}", indicator, name));
+
+            for &to in &module.connects_to {
+                graph.add_edge(name, self.names.name(to), None);
+            }
+        }
+        graph
+    }
+
     fn push_button(&mut self) {
         let mut signals: VecDeque<Pulse> = [Pulse {
             kind: Low,
-            from: "button".to_string(),
-            to: "broadcaster".to_string(),
+            from: self.button,
+            to: self.broadcaster,
         }]
         .into_iter()
         .collect();
@@ -104,16 +134,14 @@ impl Coordinator {
                 self.high_pulses += 1;
             }
 
-            if next_signal.kind == Low && next_signal.to == "rx" {
+            if next_signal.kind == Low && Some(next_signal.to) == self.rx {
                 self.done = true;
                 return;
             }
 
-            if !self.modules.contains_key(&next_signal.to) {
+            let Some(dest_mod) = self.modules[next_signal.to].as_mut() else {
                 continue;
-            }
-
-            let dest_mod = self.modules.get_mut(&next_signal.to).unwrap();
+            };
             let signal = match dest_mod.kind {
                 FlipFlop => {
                     if next_signal.kind == Low {
@@ -130,7 +158,7 @@ impl Coordinator {
                 Conjunction => {
                     dest_mod
                         .conjunction
-                        .insert(next_signal.from.to_string(), next_signal.kind);
+                        .insert(next_signal.from, next_signal.kind);
                     if dest_mod.conjunction.values().all(|&k| k == High) {
                         Some(Low)
                     } else {
@@ -140,11 +168,11 @@ impl Coordinator {
                 Broadcaster => Some(next_signal.kind),
             };
             if let Some(signal) = signal {
-                for name in &dest_mod.connects_to {
+                for &to in &dest_mod.connects_to {
                     signals.push_back(Pulse {
                         kind: signal,
-                        from: dest_mod.name.clone(),
-                        to: name.clone(),
+                        from: dest_mod.name,
+                        to,
                     });
                 }
             }
@@ -153,33 +181,38 @@ impl Coordinator {
 }
 
 impl FromStr for Coordinator {
-    type Err = Infallible;
+    type Err = anyhow::Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let modules: Vec<Module> = s.lines().map(|line| line.parse().unwrap()).collect();
-        let mut module_map: HashMap<String, Module> = modules
-            .clone()
-            .into_iter()
-            .map(|m| (m.name.clone(), m))
-            .collect();
-
-        for module in modules.iter() {
+        let mut names = Interner::new();
+        let parsed: Vec<Module> = s
+            .lines()
+            .map(|line| Module::parse(line, &mut names))
+            .collect::<anyhow::Result<_>>()?;
+        let button = names.intern("button");
+        let broadcaster = names.intern("broadcaster");
+        let rx = names.get("rx");
+
+        let mut modules = vec![None; names.len()];
+        for module in parsed.iter() {
+            let mut module = module.clone();
             if module.kind == Conjunction {
-                for possible_connections in modules.iter() {
+                for possible_connections in parsed.iter() {
                     if possible_connections.connects_to.contains(&module.name) {
-                        let actual_mod = module_map.get_mut(&module.name).unwrap();
-                        actual_mod
-                            .conjunction
-                            .insert(possible_connections.name.clone(), Low);
+                        module.conjunction.insert(possible_connections.name, Low);
                     }
                 }
             }
+            let id = module.name;
+            modules[id] = Some(module);
         }
 
-        let modules = module_map;
-
         Ok(Coordinator {
+            names,
             modules,
+            button,
+            broadcaster,
+            rx,
             low_pulses: 0,
             high_pulses: 0,
             done: false,
@@ -187,6 +220,11 @@ impl FromStr for Coordinator {
     }
 }
 
+pub fn dot() {
+    let coordinator: Coordinator = INPUT.parse().unwrap();
+    print!("{}", coordinator.to_graph().to_dot());
+}
+
 pub fn pt1() {
     let mut coordinator: Coordinator = INPUT.parse().unwrap();
     (0..1000).for_each(|_| coordinator.push_button());
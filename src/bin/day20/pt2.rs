@@ -1,18 +1,15 @@
-use num::Integer;
-use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
-use std::convert::Infallible;
+use anyhow::anyhow;
+use aoc_2023::intern::Interner;
+use aoc_2023::numtheory::lcm;
+use aoc_2023::parse;
+use itertools::Itertools;
+use std::collections::{BTreeSet, HashSet, VecDeque};
 use std::str::FromStr;
 
 const INPUT: &str = include_str!("input.dat");
 #[allow(dead_code)]
 const SAMPLE: &str = include_str!("sample.dat");
 
-#[derive(Debug, Clone, Eq, PartialEq, Hash)]
-struct Pulse {
-    from: String,
-    to: String,
-}
-
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
 enum ModuleKind {
     Broadcaster,
@@ -23,18 +20,15 @@ use ModuleKind::*;
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
 struct Module {
-    name: String,
+    name: usize,
     kind: ModuleKind,
-    connects_to: BTreeSet<String>,
+    connects_to: Vec<usize>,
 }
 
-impl FromStr for Module {
-    type Err = Infallible;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut mod_parts = s.split("->");
+impl Module {
+    fn parse(s: &str, names: &mut Interner) -> anyhow::Result<Module> {
+        let (mod_id, targets) = parse::adjacency(s)?;
 
-        let mod_id = mod_parts.next().unwrap();
         let (kind, name) = if mod_id.starts_with("broadcaster") {
             (Broadcaster, "broadcaster".to_string())
         } else if mod_id.starts_with('%') {
@@ -44,18 +38,18 @@ impl FromStr for Module {
             let name = mod_id.trim_start_matches('&').trim().to_string();
             (Conjunction, name)
         } else {
-            unreachable!();
+            return Err(anyhow!("unknown module kind in '{}'", s));
         };
 
-        let connects_to = mod_parts
-            .next()
-            .unwrap()
-            .split(',')
-            .map(|names| names.trim().to_string())
+        let connects_to = targets
+            .into_iter()
+            .sorted()
+            .dedup()
+            .map(|target| names.intern(target))
             .collect();
 
         Ok(Module {
-            name,
+            name: names.intern(&name),
             kind,
             connects_to,
         })
@@ -64,48 +58,66 @@ impl FromStr for Module {
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 struct Coordinator {
-    modules: HashMap<String, Module>,
+    names: Interner,
+    // Indexed by name id; `None` for names that are only ever connected to, such as rx
+    modules: Vec<Option<Module>>,
+    broadcaster: usize,
+    rx: Option<usize>,
 }
 
 impl FromStr for Coordinator {
-    type Err = Infallible;
+    type Err = anyhow::Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let modules = s
+        let mut names = Interner::new();
+        let parsed: Vec<Module> = s
             .lines()
-            .map(|line| {
-                let module: Module = line.parse().unwrap();
-                (module.name.clone(), module)
-            })
-            .collect();
+            .map(|line| Module::parse(line, &mut names))
+            .collect::<anyhow::Result<_>>()?;
+        let broadcaster = names.intern("broadcaster");
+        let rx = names.get("rx");
+
+        let mut modules = vec![None; names.len()];
+        for module in parsed {
+            let name = module.name;
+            modules[name] = Some(module);
+        }
 
-        Ok(Coordinator { modules })
+        Ok(Coordinator {
+            names,
+            modules,
+            broadcaster,
+            rx,
+        })
     }
 }
 
-fn pointer_tree<'a>(
-    coordinator: &'a Coordinator,
-    mut visited: HashSet<String>,
-    from: &str,
-    target: &str,
-) -> Vec<VecDeque<Module>> {
-    let module = coordinator.modules.get(from).unwrap().clone();
+// Every path of modules from `from` that leads into `target`, not counting `target` itself
+fn pointer_tree(
+    coordinator: &Coordinator,
+    mut visited: HashSet<usize>,
+    from: usize,
+    target: usize,
+) -> Vec<VecDeque<usize>> {
+    let Some(module) = coordinator.modules[from].as_ref() else {
+        return Vec::new();
+    };
     let mut trees = Vec::new();
-    visited.insert(from.to_string());
+    visited.insert(from);
 
-    for connection in module.connects_to.iter() {
-        if visited.contains(connection) {
+    for &connection in module.connects_to.iter() {
+        if visited.contains(&connection) {
             continue;
         }
         if connection == target {
-            trees.push(VecDeque::from([module.clone()]));
+            trees.push(VecDeque::from([from]));
         } else {
             let subtrees = pointer_tree(coordinator, visited.clone(), connection, target);
             for mut subtree in subtrees.into_iter() {
-                if subtree.iter().any(|m| m.name == from) {
+                if subtree.contains(&from) {
                     continue;
                 }
-                subtree.push_front(module.clone());
+                subtree.push_front(from);
                 trees.push(subtree);
             }
         }
@@ -114,75 +126,52 @@ fn pointer_tree<'a>(
     trees
 }
 
-fn _print_pointer_tree(tree: &VecDeque<Module>) {
+fn _print_pointer_tree(coordinator: &Coordinator, tree: &VecDeque<usize>) {
     let mod_names = tree
         .iter()
-        .map(|m| {
-            let indicator = match m.kind {
+        .map(|&id| {
+            let module = coordinator.modules[id].as_ref().unwrap();
+            let indicator = match module.kind {
                 FlipFlop => "%",
                 Conjunction => "&",
                 Broadcaster => "",
             };
-            format!("{}{}", indicator, m.name)
+            format!("{}{}", indicator, coordinator.names.name(id))
         })
         .collect::<Vec<_>>();
     println!("{}", mod_names.join(" -> "));
 }
 
-fn lcm<I: Iterator<Item = u64>>(inputs: I) -> u64 {
-    inputs.fold(1, |acc, x| acc.lcm(&x))
-}
-
-fn time_to_low(
-    modules: &HashMap<String, Module>,
-    pointers: &HashMap<String, HashSet<String>>,
-    target: &str,
-) -> u64 {
-    let target = &modules[target];
+fn time_to_low(modules: &[Option<Module>], pointers: &[BTreeSet<usize>], target: usize) -> u64 {
+    let target = modules[target].as_ref().unwrap();
 
     match target.kind {
         Conjunction => {
-            let submods = &pointers[target.name.as_str()];
-            let high_times = submods
+            let high_times = pointers[target.name]
                 .iter()
-                .map(|submod| time_to_high(modules, pointers, submod));
+                .map(|&submod| time_to_high(modules, pointers, submod));
 
             lcm(high_times)
         }
         FlipFlop => {
-            let pointer = &pointers[target.name.as_str()]
-                .iter()
-                .cloned()
-                .next()
-                .unwrap();
-            2 * time_to_low(modules, pointers, pointer.as_str())
+            let pointer = *pointers[target.name].first().unwrap();
+            2 * time_to_low(modules, pointers, pointer)
         }
         Broadcaster => 1,
     }
 }
 
-fn time_to_high(
-    modules: &HashMap<String, Module>,
-    pointers: &HashMap<String, HashSet<String>>,
-    target: &str,
-) -> u64 {
-    let target = &modules[target];
+fn time_to_high(modules: &[Option<Module>], pointers: &[BTreeSet<usize>], target: usize) -> u64 {
+    let target = modules[target].as_ref().unwrap();
 
     match target.kind {
-        Conjunction => {
-            let submods = &pointers[target.name.as_str()];
-            submods
-                .iter()
-                .map(|submod| time_to_high(modules, pointers, submod))
-                .sum()
-        }
+        Conjunction => pointers[target.name]
+            .iter()
+            .map(|&submod| time_to_high(modules, pointers, submod))
+            .sum(),
         FlipFlop => {
-            let pointer = &pointers[target.name.as_str()]
-                .iter()
-                .cloned()
-                .next()
-                .unwrap();
-            time_to_low(modules, pointers, pointer.as_str())
+            let pointer = *pointers[target.name].first().unwrap();
+            time_to_low(modules, pointers, pointer)
         }
         Broadcaster => panic!(),
     }
@@ -190,23 +179,24 @@ fn time_to_high(
 
 pub fn pt2() {
     let coordinator: Coordinator = INPUT.parse().unwrap();
+    let Some(rx) = coordinator.rx else {
+        eprintln!("no module feeds rx");
+        return;
+    };
 
-    let pointers = pointer_tree(&coordinator, HashSet::new(), "broadcaster", "rx");
-    // pointers.iter().for_each(_print_pointer_tree);
+    let pointers = pointer_tree(&coordinator, HashSet::new(), coordinator.broadcaster, rx);
+    // pointers.iter().for_each(|tree| _print_pointer_tree(&coordinator, tree));
 
-    let new_target = pointers[0].iter().last().unwrap().name.clone();
+    let new_target = *pointers[0].iter().last().unwrap();
 
-    let mut inv_pointers: HashMap<String, HashSet<String>> = HashMap::new();
+    let mut inv_pointers = vec![BTreeSet::new(); coordinator.modules.len()];
     for pointer_list in pointers.iter() {
         (1..pointer_list.len()).rev().for_each(|i| {
-            let from = &pointer_list[i];
-            let to = &pointer_list[i - 1];
-            let set = inv_pointers.entry(from.name.clone()).or_default();
-            set.insert(to.name.clone());
+            inv_pointers[pointer_list[i]].insert(pointer_list[i - 1]);
         });
     }
 
-    let result = time_to_low(&coordinator.modules, &inv_pointers, &new_target);
+    let result = time_to_low(&coordinator.modules, &inv_pointers, new_target);
 
     println!("{}", result);
 }
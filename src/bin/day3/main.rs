@@ -1,89 +1,170 @@
-use lazy_static::lazy_static;
-use regex::Regex;
-use std::collections::{HashMap, HashSet};
+use aoc_2023::grid::Grid;
+use aoc_2023::parse;
+use itertools::Itertools;
 
 const INPUT: &str = include_str!("input.dat");
 
+#[derive(Debug, Clone)]
+struct PartNum {
+    pub x: usize,
+    pub y: usize,
+    pub len: usize,
+    pub num: u32,
+}
+
+#[derive(Debug, Clone)]
+struct Symbol {
+    pub x: usize,
+    pub y: usize,
+    pub c: char,
+}
+
+// The schematic with every number and symbol indexed. Adjacency is kept both ways: the numbers
+// touching each symbol, and the symbols touching each number.
 struct Engine {
-    lines: Vec<String>,
+    numbers: Vec<PartNum>,
+    symbols: Vec<Symbol>,
+    numbers_by_symbol: Vec<Vec<usize>>,
+    symbols_by_number: Vec<Vec<usize>>,
 }
 
 impl Engine {
     fn load(inp: &str) -> Self {
+        let grid = Grid::from_rows(parse::char_grid(inp));
+
+        let mut numbers = Vec::new();
+        // Which number, if any, covers each cell
+        let mut number_at = Grid::new(grid.width(), grid.height(), None);
+        for (y, row) in grid.rows().enumerate() {
+            let runs = row.iter().enumerate().group_by(|(_, c)| c.is_ascii_digit());
+            for (_, run) in runs.into_iter().filter(|(is_digit, _)| *is_digit) {
+                let run: Vec<(usize, &char)> = run.collect();
+                let x = run[0].0;
+                let num = run
+                    .iter()
+                    .fold(0, |num, (_, c)| num * 10 + c.to_digit(10).unwrap());
+
+                (x..x + run.len()).for_each(|x| number_at.set(x, y, Some(numbers.len())));
+                numbers.push(PartNum {
+                    x,
+                    y,
+                    len: run.len(),
+                    num,
+                });
+            }
+        }
+
+        let symbols: Vec<Symbol> = grid
+            .positions()
+            .map(|(x, y)| Symbol {
+                x,
+                y,
+                c: *grid.get(x, y),
+            })
+            .filter(|symbol| !symbol.c.is_ascii_digit() && symbol.c != '.')
+            .collect();
+
+        let mut symbols_by_number = vec![Vec::new(); numbers.len()];
+        let numbers_by_symbol: Vec<Vec<usize>> = symbols
+            .iter()
+            .enumerate()
+            .map(|(symbol_id, symbol)| {
+                let touching: Vec<usize> = neighbours(&grid, symbol.x, symbol.y)
+                    .filter_map(|(x, y)| *number_at.get(x, y))
+                    .sorted()
+                    .dedup()
+                    .collect();
+                touching
+                    .iter()
+                    .for_each(|&number_id| symbols_by_number[number_id].push(symbol_id));
+                touching
+            })
+            .collect();
+
         Self {
-            lines: inp.lines().map(|line| line.to_string()).collect(),
+            numbers,
+            symbols,
+            numbers_by_symbol,
+            symbols_by_number,
         }
     }
 
-    fn get(&self, x: i32, y: i32) -> Option<char> {
-        if x < 0 || y < 0 {
-            return None;
-        }
-        self.lines
-            .get(y as usize)
-            .and_then(|line| line.chars().nth(x as usize))
+    // Numbers touching at least one symbol
+    fn part_numbers(&self) -> impl Iterator<Item = &PartNum> {
+        self.numbers
+            .iter()
+            .zip(self.symbols_by_number.iter())
+            .filter(|(_, symbols)| !symbols.is_empty())
+            .map(|(number, _)| number)
     }
 
-    fn find_possible_part_nums(&self) -> Vec<PartNum> {
-        self.lines
+    // Every `c` symbol touching exactly `arity` numbers, along with those numbers
+    fn symbols_with_arity(
+        &self,
+        c: char,
+        arity: usize,
+    ) -> impl Iterator<Item = (&Symbol, Vec<&PartNum>)> {
+        self.symbols
             .iter()
-            .enumerate()
-            .flat_map(|(y, line)| {
-                PART_NO_RE.find_iter(line).map(move |re_match| {
-                    let num: u32 = re_match.as_str().parse().unwrap();
-                    let x = re_match.start();
-                    let len = re_match.len();
-                    PartNum { x, y, len, num }
-                })
+            .zip(self.numbers_by_symbol.iter())
+            .filter(move |(symbol, numbers)| symbol.c == c && numbers.len() == arity)
+            .map(|(symbol, numbers)| {
+                let numbers = numbers.iter().map(|&id| &self.numbers[id]).collect();
+                (symbol, numbers)
             })
-            .collect()
     }
 }
 
-struct PartNum {
-    pub x: usize,
-    pub y: usize,
-    pub len: usize,
-    pub num: u32,
+fn neighbours<T>(grid: &Grid<T>, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+    (-1..=1)
+        .cartesian_product(-1..=1)
+        .filter(|&(dx, dy)| (dx, dy) != (0, 0))
+        .filter_map(move |(dx, dy)| {
+            let x = x.checked_add_signed(dx)?;
+            let y = y.checked_add_signed(dy)?;
+            grid.in_bounds(x, y).then_some((x, y))
+        })
 }
 
-lazy_static! {
-    static ref PART_NO_RE: Regex = Regex::new(r"\d+").unwrap();
-}
+// Handles `--around <symbol> <count>`, listing the symbols touching exactly that many numbers
+fn query_from_args() -> Option<anyhow::Result<()>> {
+    let mut args = std::env::args().skip_while(|arg| arg != "--around");
+    args.next()?;
 
-mod pt1 {
-    use super::*;
+    let mut query = || {
+        let c = args
+            .next()
+            .and_then(|arg| arg.chars().next())
+            .unwrap_or('*');
+        let arity = args.next().map_or(Ok(2), |arg| arg.parse())?;
 
-    impl Engine {}
-
-    fn check_surroundings(engine: &Engine, x: i32, y: i32) -> bool {
-        for i in (x - 1)..=(x + 1) {
-            for j in (y - 1)..=(y + 1) {
-                if let Some(c) = engine.get(i, j) {
-                    if !c.is_ascii_digit() && c != '.' {
-                        return true;
-                    }
-                }
-            }
+        let engine = Engine::load(INPUT);
+        for (symbol, numbers) in engine.symbols_with_arity(c, arity) {
+            let product: u64 = numbers.iter().map(|n| n.num as u64).product();
+            println!(
+                "{} at ({}, {}): {} (product {})",
+                symbol.c,
+                symbol.x,
+                symbol.y,
+                numbers
+                    .iter()
+                    .map(|n| format!("{} at ({}..{}, {})", n.num, n.x, n.x + n.len, n.y))
+                    .format(", "),
+                product
+            );
         }
+        Ok(())
+    };
+    Some(query())
+}
 
-        false
-    }
-
-    fn is_valid_part_num(part_num: &PartNum, engine: &Engine) -> bool {
-        (part_num.x..(part_num.x + part_num.len))
-            .any(|x| check_surroundings(engine, x as i32, part_num.y as i32))
-    }
+mod pt1 {
+    use super::*;
 
     pub fn pt1() {
         let engine = Engine::load(INPUT);
-        let part_nums = engine.find_possible_part_nums();
 
-        let result: u32 = part_nums
-            .into_iter()
-            .filter(|part_num| is_valid_part_num(part_num, &engine))
-            .map(|part_num| part_num.num)
-            .sum();
+        let result: u32 = engine.part_numbers().map(|part_num| part_num.num).sum();
 
         println!("{}", result);
     }
@@ -92,69 +173,24 @@ mod pt1 {
 mod pt2 {
     use super::*;
 
-    impl PartNum {
-        fn adjacent_gears(&self, engine: &Engine) -> Vec<(usize, usize)> {
-            (self.x..(self.x + self.len))
-                .flat_map(|x| self.adjacent_gears_to_loc(x, self.y, engine))
-                .collect()
-        }
-
-        fn adjacent_gears_to_loc(
-            &self,
-            x: usize,
-            y: usize,
-            engine: &Engine,
-        ) -> Vec<(usize, usize)> {
-            let x = x as i32;
-            let y = y as i32;
-
-            ((x - 1)..=(x + 1))
-                .flat_map(|i| ((y - 1)..=(y + 1)).map(move |j| (i, j)))
-                .filter_map(|(i, j)| {
-                    engine
-                        .get(i, j)
-                        .and_then(|c| (c == '*').then_some((i as usize, j as usize)))
-                })
-                .collect()
-        }
-    }
-
-    fn find_gear_ratios(engine: &Engine, part_nums: &Vec<PartNum>) -> Vec<u32> {
-        let mut possible_gears: HashMap<usize, HashMap<usize, HashSet<u32>>> = HashMap::new();
-
-        for part_num in part_nums {
-            for (x, y) in part_num.adjacent_gears(engine) {
-                possible_gears
-                    .entry(x)
-                    .or_default()
-                    .entry(y)
-                    .or_default()
-                    .insert(part_num.num);
-            }
-        }
-
-        possible_gears
-            .iter()
-            .flat_map(|(_, inner)| {
-                inner
-                    .iter()
-                    .filter(|(_, nums)| nums.len() == 2)
-                    .map(|(_, nums)| nums.iter().product())
-            })
-            .collect()
-    }
-
     pub fn pt2() {
         let engine = Engine::load(INPUT);
-        let part_nums = engine.find_possible_part_nums();
-        let ratios = find_gear_ratios(&engine, &part_nums);
-        let result: u32 = ratios.iter().sum();
+
+        let result: u32 = engine
+            .symbols_with_arity('*', 2)
+            .map(|(_, numbers)| numbers.iter().map(|n| n.num).product::<u32>())
+            .sum();
 
         println!("{}", result);
     }
 }
 
 fn main() {
+    if let Some(result) = query_from_args() {
+        result.unwrap();
+        return;
+    }
+
     println!("Part 1:");
     pt1::pt1();
     println!("Part 2:");
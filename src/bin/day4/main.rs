@@ -1,8 +1,8 @@
+use anyhow::anyhow;
+use aoc_2023::parse;
 use lazy_static::lazy_static;
 use regex::Regex;
 use std::collections::HashSet;
-use std::collections::VecDeque;
-use std::convert::Infallible;
 use std::str::FromStr;
 
 const INPUT: &str = include_str!("input.dat");
@@ -27,34 +27,25 @@ impl Game {
 }
 
 impl FromStr for Game {
-    type Err = Infallible;
+    type Err = anyhow::Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut id_split = s.split(':');
+        let (card_id_part, hand_part) = s
+            .split_once(':')
+            .ok_or_else(|| anyhow!("missing card id in '{}'", s))?;
 
-        let card_id_part = id_split.next().unwrap();
         let id = card_id_part
             .split_ascii_whitespace()
             .last()
-            .unwrap()
-            .parse()
-            .unwrap();
+            .ok_or_else(|| anyhow!("missing card id in '{}'", s))?
+            .parse()?;
 
-        let hand_part = id_split.next().unwrap();
+        let (winners_part, hand_part) = hand_part
+            .split_once('|')
+            .ok_or_else(|| anyhow!("missing '|' in '{}'", s))?;
 
-        let mut winners_and_hand = hand_part.split('|');
-
-        let winners_part = winners_and_hand.next().unwrap();
-        let winners = winners_part
-            .split_ascii_whitespace()
-            .map(|n| str::parse(n).unwrap())
-            .collect();
-
-        let hand_part = winners_and_hand.next().unwrap();
-        let actual = hand_part
-            .split_ascii_whitespace()
-            .map(|n| str::parse(n).unwrap())
-            .collect();
+        let winners = parse::numbers(winners_part)?.into_iter().collect();
+        let actual = parse::numbers(hand_part)?.into_iter().collect();
 
         Ok(Self {
             id,
@@ -92,37 +83,75 @@ mod pt1 {
 mod pt2 {
     use super::*;
 
-    impl Game {
-        fn get_next_games<'a>(&self, games: &'a [Game]) -> Vec<&'a Game> {
-            (0..self.num_winners())
-                .map(|i| &games[(self.id + i) as usize])
-                .collect()
+    #[derive(Debug, Clone, Default, Eq, PartialEq)]
+    pub struct Cascade {
+        // Copies held of each card, in input order, originals included
+        pub copies: Vec<u64>,
+        // Copies won of cards past the end of the table, which don't exist
+        pub overflow: u64,
+    }
+
+    impl Cascade {
+        pub fn total(&self) -> u64 {
+            self.copies.iter().sum()
         }
     }
 
-    pub fn pt2() {
+    // Every copy of card i wins one copy of each of the next `num_winners` cards. Rather than
+    // visiting those cards, the copies join a running total of wins that lapses once the range
+    // ends, so each card costs O(1).
+    pub fn cascade(games: &[Game]) -> Cascade {
+        let mut copies = Vec::with_capacity(games.len());
+        let mut expiring = vec![0u64; games.len() + 1];
+        let mut won = 0u64;
+        let mut overflow = 0u64;
+
+        for (i, game) in games.iter().enumerate() {
+            won -= expiring[i];
+            let held = 1 + won;
+            copies.push(held);
+
+            let end = i + 1 + game.num_winners() as usize;
+            let clamped = end.min(games.len());
+            won += held;
+            expiring[clamped] += held;
+            overflow += held * (end - clamped) as u64;
+        }
+
+        Cascade { copies, overflow }
+    }
+
+    pub fn copies() {
         let games = INPUT
             .lines()
             .map(|l| l.parse::<Game>().unwrap())
             .collect::<Vec<_>>();
 
-        let mut needs_processing: VecDeque<&Game> = games.iter().collect();
-
-        let mut count = 0u32;
-        while !needs_processing.is_empty() {
-            count += 1;
-            let curr_game = needs_processing.pop_front().unwrap();
-            let next_games = curr_game.get_next_games(&games);
-            next_games
-                .into_iter()
-                .for_each(|g| needs_processing.push_back(g));
+        let cascade = cascade(&games);
+        for (game, copies) in games.iter().zip(cascade.copies.iter()) {
+            println!("Card {}: {}", game.id, copies);
         }
+        println!("Past the last card: {}", cascade.overflow);
+    }
+
+    pub fn pt2() {
+        let games = INPUT
+            .lines()
+            .map(|l| l.parse::<Game>().unwrap())
+            .collect::<Vec<_>>();
+
+        let result = cascade(&games).total();
 
-        println!("{}", count);
+        println!("{}", result);
     }
 }
 
 fn main() {
+    if std::env::args().any(|arg| arg == "--copies") {
+        pt2::copies();
+        return;
+    }
+
     println!("Part 1:");
     pt1::pt1();
     println!("Part 2:");
@@ -0,0 +1,324 @@
+use anyhow::{anyhow, bail};
+use aoc_2023::interval::{normalize, Interval};
+use aoc_2023::parse;
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::fmt;
+use std::str::FromStr;
+
+pub type SeedRange = Interval<u64>;
+
+lazy_static! {
+    static ref HEADER: Regex = Regex::new(r"^(\w+)-to-(\w+) map:$").unwrap();
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct Map {
+    dst_range_start: u64,
+    src_range: SeedRange,
+}
+
+impl Map {
+    fn identity(range: SeedRange) -> Map {
+        Map {
+            dst_range_start: range.start,
+            src_range: range,
+        }
+    }
+
+    // Maps the part of `range` this map covers
+    fn map_range(&self, range: SeedRange) -> Option<SeedRange> {
+        let overlap = range.intersect(&self.src_range)?;
+        let start = self.dst_range_start + (overlap.start - self.src_range.start);
+        Some(SeedRange::from_len(start, overlap.len()))
+    }
+
+    fn dst_range(&self) -> SeedRange {
+        SeedRange::from_len(self.dst_range_start, self.src_range.len())
+    }
+
+    fn inverted(&self) -> Map {
+        Map {
+            dst_range_start: self.src_range.start,
+            src_range: self.dst_range(),
+        }
+    }
+
+    // Joins `next` onto the end of this map when the two line up into one linear piece
+    fn merge(&self, next: &Map) -> Option<Map> {
+        if self.src_range.end != next.src_range.start
+            || self.dst_range().end != next.dst_range_start
+        {
+            return None;
+        }
+
+        Some(Map {
+            dst_range_start: self.dst_range_start,
+            src_range: SeedRange::new(self.src_range.start, next.src_range.end),
+        })
+    }
+}
+
+impl FromStr for Map {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let [dst_range_start, src_range_start, range_len] = parse::numbers(s)?[..] else {
+            bail!("expected three numbers in '{}'", s);
+        };
+
+        let src_range = SeedRange::checked_from_len(src_range_start, range_len);
+        let dst_range = SeedRange::checked_from_len(dst_range_start, range_len);
+        let (Some(src_range), Some(_)) = (src_range, dst_range) else {
+            bail!("range in '{}' runs past {}", s, u64::MAX);
+        };
+
+        Ok(Self {
+            dst_range_start,
+            src_range,
+        })
+    }
+}
+
+// One "X-to-Y map" section. The maps cover every number: gaps between the listed ranges are
+// filled with identity maps, so inverting a stage is just inverting each map.
+#[derive(Debug, Clone)]
+pub struct Stage {
+    pub from: String,
+    pub to: String,
+    maps: Vec<Map>,
+}
+
+impl Stage {
+    fn new(from: &str, to: &str, mut listed: Vec<Map>) -> anyhow::Result<Stage> {
+        listed.retain(|map| !map.src_range.is_empty());
+        listed.sort_by_key(|map| map.src_range.start);
+
+        let mut maps = Vec::new();
+        let mut covered = 0;
+        for map in listed {
+            if map.src_range.start < covered {
+                bail!("overlapping source ranges in the {}-to-{} map", from, to);
+            }
+            if map.src_range.start > covered {
+                maps.push(Map::identity(SeedRange::new(covered, map.src_range.start)));
+            }
+            covered = map.src_range.end;
+            maps.push(map);
+        }
+        if covered < u64::MAX {
+            maps.push(Map::identity(SeedRange::new(covered, u64::MAX)));
+        }
+
+        Ok(Stage {
+            from: from.to_string(),
+            to: to.to_string(),
+            maps,
+        })
+    }
+
+    fn inverted(&self) -> Stage {
+        Stage {
+            from: self.to.clone(),
+            to: self.from.clone(),
+            maps: self.maps.iter().map(Map::inverted).collect(),
+        }
+    }
+
+    fn identity(category: &str) -> Stage {
+        Stage {
+            from: category.to_string(),
+            to: category.to_string(),
+            maps: vec![Map::identity(SeedRange::new(0, u64::MAX))],
+        }
+    }
+
+    // This stage followed by `next`, as a single stage
+    pub fn compose(&self, next: &Stage) -> Stage {
+        let mut maps: Vec<Map> = Vec::new();
+        for map in &self.maps {
+            for piece in &next.maps {
+                let Some(overlap) = map.dst_range().intersect(&piece.src_range) else {
+                    continue;
+                };
+                let composed = Map {
+                    dst_range_start: piece.map_range(overlap).unwrap().start,
+                    src_range: SeedRange::from_len(
+                        map.src_range.start + (overlap.start - map.dst_range_start),
+                        overlap.len(),
+                    ),
+                };
+
+                match maps.last().and_then(|last| last.merge(&composed)) {
+                    Some(merged) => *maps.last_mut().unwrap() = merged,
+                    None => maps.push(composed),
+                }
+            }
+        }
+        maps.sort_by_key(|map| map.src_range.start);
+
+        Stage {
+            from: self.from.clone(),
+            to: next.to.clone(),
+            maps,
+        }
+    }
+
+    // Looks up a single number with a binary search over the maps, so O(log n) in the number of
+    // pieces however many stages were composed into this one. Only meaningful for forward
+    // stages, where sources never overlap.
+    pub fn lookup(&self, n: u64) -> u64 {
+        let i = self.maps.partition_point(|map| map.src_range.end <= n);
+        self.maps
+            .get(i)
+            .and_then(|map| map.map_range(SeedRange::from_len(n, 1)))
+            .map_or(n, |range| range.start)
+    }
+
+    // The linear pieces of this stage restricted to `domain`, as (source, destination) pairs
+    // ordered by destination.
+    pub fn pieces_within(&self, domain: &[SeedRange]) -> Vec<(SeedRange, SeedRange)> {
+        let mut pieces: Vec<_> = domain
+            .iter()
+            .flat_map(|range| {
+                self.maps.iter().filter_map(move |map| {
+                    let src = range.intersect(&map.src_range)?;
+                    Some((src, map.map_range(src)?))
+                })
+            })
+            .collect();
+        pieces.sort_by_key(|(_, dst)| dst.start);
+        pieces
+    }
+
+    pub fn map_ranges(&self, ranges: &[SeedRange]) -> Vec<SeedRange> {
+        normalize(
+            ranges
+                .iter()
+                .flat_map(|&range| self.maps.iter().filter_map(move |map| map.map_range(range))),
+        )
+    }
+}
+
+// Prints the breakpoint table, one linear piece per row.
+impl fmt::Display for Stage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{:>42} {:>42} {:>21}", self.from, self.to, "offset")?;
+        for map in &self.maps {
+            let offset = map.dst_range_start as i128 - map.src_range.start as i128;
+            writeln!(
+                f,
+                "{:>42} {:>42} {:>+21}",
+                format!("{}..{}", map.src_range.start, map.src_range.end),
+                format!("{}..{}", map.dst_range_start, map.dst_range().end),
+                offset
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Stage {
+    type Err = anyhow::Error;
+
+    fn from_str(block: &str) -> Result<Self, Self::Err> {
+        let mut lines = block.lines();
+        let header = lines.next().unwrap_or_default();
+        let captures = HEADER
+            .captures(header)
+            .ok_or_else(|| anyhow!("expected an 'X-to-Y map:' header, got '{}'", header))?;
+
+        let maps = lines.map(str::parse).collect::<anyhow::Result<_>>()?;
+        Stage::new(&captures[1], &captures[2], maps)
+    }
+}
+
+#[derive(Debug)]
+pub struct Almanac {
+    pub seeds: Vec<u64>,
+    stages: Vec<Stage>,
+}
+
+impl Almanac {
+    // The seeds line read as (start, length) pairs
+    pub fn seed_ranges(&self) -> anyhow::Result<Vec<SeedRange>> {
+        if !self.seeds.len().is_multiple_of(2) {
+            bail!(
+                "{} seeds can't be read as (start, length) pairs",
+                self.seeds.len()
+            );
+        }
+
+        self.seeds
+            .chunks(2)
+            .map(|pair| {
+                SeedRange::checked_from_len(pair[0], pair[1]).ok_or_else(|| {
+                    anyhow!("seed range {} {} runs past {}", pair[0], pair[1], u64::MAX)
+                })
+            })
+            .collect()
+    }
+
+    fn forward_route(&self, from: &str, to: &str) -> Option<Vec<&Stage>> {
+        let mut route = Vec::new();
+        let mut category = from;
+        while category != to {
+            let stage = self.stages.iter().find(|stage| stage.from == category)?;
+            route.push(stage);
+            category = &stage.to;
+
+            if route.len() > self.stages.len() {
+                return None;
+            }
+        }
+        Some(route)
+    }
+
+    // The stages leading from one category to another, inverted when `to` comes before `from`
+    pub fn route(&self, from: &str, to: &str) -> anyhow::Result<Vec<Stage>> {
+        if let Some(route) = self.forward_route(from, to) {
+            Ok(route.into_iter().cloned().collect())
+        } else if let Some(route) = self.forward_route(to, from) {
+            Ok(route.into_iter().rev().map(Stage::inverted).collect())
+        } else {
+            Err(anyhow!("no chain of maps links {} and {}", from, to))
+        }
+    }
+
+    // The whole route from one category to another as a single stage
+    pub fn composed(&self, from: &str, to: &str) -> anyhow::Result<Stage> {
+        Ok(self
+            .route(from, to)?
+            .iter()
+            .fold(Stage::identity(from), |composed, stage| {
+                composed.compose(stage)
+            }))
+    }
+
+    pub fn map_ranges(
+        &self,
+        from: &str,
+        to: &str,
+        ranges: &[SeedRange],
+    ) -> anyhow::Result<Vec<SeedRange>> {
+        let ranges = normalize(ranges.iter().copied());
+        Ok(self
+            .route(from, to)?
+            .iter()
+            .fold(ranges, |ranges, stage| stage.map_ranges(&ranges)))
+    }
+}
+
+impl FromStr for Almanac {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut blocks = parse::blocks(s);
+
+        let seeds_block = blocks.next().ok_or_else(|| anyhow!("missing seeds line"))?;
+        let (_, seeds) = parse::labeled_numbers(seeds_block)?;
+        let stages = blocks.map(str::parse).collect::<anyhow::Result<_>>()?;
+
+        Ok(Almanac { seeds, stages })
+    }
+}
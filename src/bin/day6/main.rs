@@ -1,5 +1,6 @@
+use aoc_2023::parse;
 use lazy_static::lazy_static;
-use rayon::prelude::*;
+use num::{BigUint, One, Zero};
 use regex::Regex;
 
 const INPUT: &str = include_str!("input.dat");
@@ -10,42 +11,55 @@ lazy_static! {
     static ref EXAMPLE: Regex = Regex::new(r"example").unwrap();
 }
 
-fn distance(t: u64, h: u64) -> u64 {
+fn distance(t: &BigUint, h: &BigUint) -> BigUint {
     h * (t - h)
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 struct Race {
-    t: u64,
-    d: u64,
+    t: BigUint,
+    d: BigUint,
+}
+
+impl Race {
+    fn wins(&self, h: &BigUint) -> bool {
+        distance(&self.t, h) > self.d
+    }
+
+    // Winning holds are the h with h * (t - h) > d, the integers strictly between the roots
+    // (t ± sqrt(t^2 - 4d)) / 2. The integer square root puts the estimate of the lower bound
+    // within one of the truth; stepping to the exact boundary keeps h * (t - h) == d a loss.
+    fn num_ways_to_win(&self) -> BigUint {
+        let one = BigUint::one();
+        let half = &self.t / 2u32;
+        if !self.wins(&half) {
+            return BigUint::zero();
+        }
+
+        let discriminant = &self.t * &self.t - 4u32 * &self.d;
+        let mut lo = (&self.t - discriminant.sqrt()) / 2u32;
+        while !lo.is_zero() && self.wins(&(&lo - &one)) {
+            lo -= &one;
+        }
+        while !self.wins(&lo) {
+            lo += &one;
+        }
+
+        // Distances are symmetric about t / 2
+        let hi = &self.t - &lo;
+        hi - lo + one
+    }
 }
 
 fn parse_races(inp: &str) -> Vec<Race> {
     let mut lines = inp.lines();
 
-    let time_line = lines.next().unwrap();
-    let times = time_line
-        .split(':')
-        .last()
-        .unwrap()
-        .trim()
-        .split_ascii_whitespace()
-        .map(|x| x.parse::<u64>().unwrap())
-        .collect::<Vec<u64>>();
-
-    let distance_line = lines.next().unwrap();
-    let distances = distance_line
-        .split(':')
-        .last()
-        .unwrap()
-        .trim()
-        .split_ascii_whitespace()
-        .map(|x| x.parse::<u64>().unwrap())
-        .collect::<Vec<u64>>();
+    let (_, times) = parse::labeled_numbers::<BigUint>(lines.next().unwrap()).unwrap();
+    let (_, distances) = parse::labeled_numbers::<BigUint>(lines.next().unwrap()).unwrap();
 
     times
         .into_iter()
-        .zip(distances.into_iter())
+        .zip(distances)
         .map(|(t, d)| Race { t, d })
         .collect()
 }
@@ -53,29 +67,8 @@ fn parse_races(inp: &str) -> Vec<Race> {
 fn parse_single_race(inp: &str) -> Race {
     let mut lines = inp.lines();
 
-    let time_line = lines.next().unwrap();
-    let time: u64 = time_line
-        .split(':')
-        .last()
-        .unwrap()
-        .trim()
-        .split_ascii_whitespace()
-        .collect::<Vec<_>>()
-        .join("")
-        .parse()
-        .unwrap();
-
-    let distance_line = lines.next().unwrap();
-    let distance = distance_line
-        .split(':')
-        .last()
-        .unwrap()
-        .trim()
-        .split_ascii_whitespace()
-        .collect::<Vec<_>>()
-        .join("")
-        .parse()
-        .unwrap();
+    let (_, time) = parse::labeled_joined_number(lines.next().unwrap()).unwrap();
+    let (_, distance) = parse::labeled_joined_number(lines.next().unwrap()).unwrap();
 
     Race {
         t: time,
@@ -89,32 +82,18 @@ mod pt1 {
     pub fn pt1() {
         let races = parse_races(INPUT);
 
-        let result: u64 = races
-            .into_iter()
-            .map(|r| {
-                (0..r.t)
-                    .map(move |h| distance(r.t, h))
-                    .filter(move |d| *d > r.d)
-                    .count() as u64
-            })
-            .product();
+        let result: BigUint = races.iter().map(Race::num_ways_to_win).product();
 
         println!("{}", result);
     }
 }
 
 mod pt2 {
-    use rayon::iter::IntoParallelIterator;
-
     use super::*;
 
     pub fn pt2() {
         let race = parse_single_race(INPUT);
-        let result = (0..race.t)
-            .into_par_iter()
-            .map(move |h| distance(race.t, h))
-            .filter(move |d| *d > race.d)
-            .count() as u64;
+        let result = race.num_ways_to_win();
 
         println!("{}", result);
     }
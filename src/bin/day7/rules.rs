@@ -0,0 +1,261 @@
+use anyhow::{anyhow, bail};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt;
+
+// A hand category such as "Full house", given as the group sizes it needs, e.g. [3, 2].
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Category {
+    pub name: String,
+    pub groups: Vec<usize>,
+}
+
+impl Category {
+    pub fn new(name: &str, groups: &[usize]) -> Category {
+        let mut groups = groups.to_vec();
+        groups.sort_unstable_by(|a, b| b.cmp(a));
+
+        Category {
+            name: name.to_string(),
+            groups,
+        }
+    }
+
+    // Wildcards needed to build the groups out of `counts` (sorted largest first). Pairing the
+    // largest wanted group with the largest group in hand never needs more wildcards than any
+    // other pairing.
+    fn wildcards_needed(&self, counts: &[usize]) -> usize {
+        self.groups
+            .iter()
+            .enumerate()
+            .map(|(i, &want)| want.saturating_sub(counts.get(i).copied().unwrap_or(0)))
+            .sum()
+    }
+}
+
+pub fn standard_categories() -> Vec<Category> {
+    vec![
+        Category::new("Five of a kind", &[5]),
+        Category::new("Four of a kind", &[4]),
+        Category::new("Full house", &[3, 2]),
+        Category::new("Three of a kind", &[3]),
+        Category::new("Two pair", &[2, 2]),
+        Category::new("One pair", &[2]),
+        Category::new("High card", &[]),
+    ]
+}
+
+// Parses "Name=3,2;Other name=2" into categories, strongest first.
+pub fn parse_categories(s: &str) -> anyhow::Result<Vec<Category>> {
+    s.split(';')
+        .map(|entry| {
+            let (name, groups) = entry
+                .split_once('=')
+                .ok_or_else(|| anyhow!("expected 'name=sizes' in '{}'", entry))?;
+            let groups = groups
+                .split(',')
+                .filter(|size| !size.trim().is_empty())
+                .map(|size| Ok(size.trim().parse()?))
+                .collect::<anyhow::Result<Vec<usize>>>()?;
+            Ok(Category::new(name.trim(), &groups))
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone)]
+pub struct Rules {
+    hand_size: usize,
+    // Weakest first
+    rank_order: Vec<char>,
+    wildcards: Vec<char>,
+    // Strongest first; a hand takes the first category it can complete
+    categories: Vec<Category>,
+}
+
+impl Rules {
+    pub fn standard() -> Rules {
+        Rules {
+            hand_size: 5,
+            rank_order: "23456789TJQKA".chars().collect(),
+            wildcards: Vec::new(),
+            categories: standard_categories(),
+        }
+    }
+
+    pub fn jokers_wild() -> Rules {
+        Rules::standard()
+            .with_rank_order("J23456789TQKA")
+            .with_wildcards("J")
+    }
+
+    pub fn with_hand_size(mut self, hand_size: usize) -> Rules {
+        self.hand_size = hand_size;
+        self
+    }
+
+    pub fn with_rank_order(mut self, weakest_first: &str) -> Rules {
+        self.rank_order = weakest_first.chars().collect();
+        self
+    }
+
+    pub fn with_wildcards(mut self, labels: &str) -> Rules {
+        self.wildcards = labels.chars().collect();
+        self
+    }
+
+    pub fn with_categories(mut self, strongest_first: Vec<Category>) -> Rules {
+        self.categories = strongest_first;
+        self
+    }
+
+    // Standard rules adjusted by --hand-size, --ranks, --wildcards and --categories, or None if
+    // none of them were given.
+    pub fn from_args() -> Option<anyhow::Result<Rules>> {
+        let args: Vec<String> = std::env::args().collect();
+        // Some(Err) when the flag is given without a value after it
+        let value = |flag: &str| {
+            args.iter().position(|arg| arg == flag).map(|i| {
+                args.get(i + 1)
+                    .filter(|value| !value.starts_with("--"))
+                    .cloned()
+                    .ok_or_else(|| anyhow!("{} needs a value", flag))
+            })
+        };
+
+        let hand_size = value("--hand-size");
+        let ranks = value("--ranks");
+        let wildcards = value("--wildcards");
+        let categories = value("--categories");
+        if hand_size.is_none() && ranks.is_none() && wildcards.is_none() && categories.is_none() {
+            return None;
+        }
+
+        let build = || {
+            let mut rules = Rules::standard();
+            if let Some(hand_size) = hand_size {
+                rules = rules.with_hand_size(hand_size?.parse()?);
+            }
+            if let Some(ranks) = ranks {
+                rules = rules.with_rank_order(&ranks?);
+            }
+            if let Some(wildcards) = wildcards {
+                rules = rules.with_wildcards(&wildcards?);
+            }
+            if let Some(categories) = categories {
+                rules = rules.with_categories(parse_categories(&categories?)?);
+            }
+            Ok(rules)
+        };
+        Some(build())
+    }
+
+    fn rank(&self, card: char) -> Option<usize> {
+        self.rank_order.iter().position(|&c| c == card)
+    }
+
+    pub fn check(&self, cards: &[char]) -> anyhow::Result<()> {
+        if cards.len() != self.hand_size {
+            bail!(
+                "hand '{}' has {} cards, expected {}",
+                cards.iter().collect::<String>(),
+                cards.len(),
+                self.hand_size
+            );
+        }
+        if let Some(&card) = cards.iter().find(|&&c| self.rank(c).is_none()) {
+            return Err(anyhow!("card '{}' is not in the rank order", card));
+        }
+        Ok(())
+    }
+
+    // Index into the categories, 0 being the strongest. Hands matching none of them come after
+    // the last one.
+    pub fn category(&self, cards: &[char]) -> usize {
+        let mut counts: HashMap<char, usize> = HashMap::new();
+        let mut wild = 0;
+        for &card in cards {
+            if self.wildcards.contains(&card) {
+                wild += 1;
+            } else {
+                *counts.entry(card).or_default() += 1;
+            }
+        }
+
+        let mut counts: Vec<usize> = counts.into_values().collect();
+        counts.sort_unstable_by(|a, b| b.cmp(a));
+
+        self.categories
+            .iter()
+            .position(|category| category.wildcards_needed(&counts) <= wild)
+            .unwrap_or(self.categories.len())
+    }
+
+    fn category_name(&self, index: usize) -> &str {
+        self.categories
+            .get(index)
+            .map_or("Uncategorised", |category| &category.name)
+    }
+
+    fn ranks(&self, cards: &[char]) -> Vec<Option<usize>> {
+        cards.iter().map(|&card| self.rank(card)).collect()
+    }
+
+    pub fn compare(&self, a: &[char], b: &[char]) -> Ordering {
+        self.category(b)
+            .cmp(&self.category(a))
+            .then_with(|| self.ranks(a).cmp(&self.ranks(b)))
+    }
+
+    // Why `cards` ranks where it does, relative to the next weaker hand if there is one.
+    pub fn explain(&self, cards: &[char], weaker: Option<&[char]>) -> Explanation {
+        let category = self.category(cards);
+        let tie_break = weaker
+            .filter(|weaker| self.category(weaker) == category)
+            .and_then(|weaker| {
+                cards
+                    .iter()
+                    .zip(weaker.iter())
+                    .enumerate()
+                    .find(|(_, (a, b))| a != b)
+                    .map(|(position, (&card, &over))| TieBreak {
+                        position,
+                        card,
+                        over,
+                    })
+            });
+
+        Explanation {
+            category: self.category_name(category).to_string(),
+            tie_break,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct TieBreak {
+    pub position: usize,
+    pub card: char,
+    pub over: char,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Explanation {
+    pub category: String,
+    pub tie_break: Option<TieBreak>,
+}
+
+impl fmt::Display for Explanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.category)?;
+        if let Some(tie_break) = self.tie_break {
+            write!(
+                f,
+                ", card {} {} beats {}",
+                tie_break.position + 1,
+                tie_break.card,
+                tie_break.over
+            )?;
+        }
+        Ok(())
+    }
+}
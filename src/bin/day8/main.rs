@@ -1,9 +1,34 @@
+mod network;
 mod pt1;
 mod pt2;
 
+// The value after `flag`, if it was given
+fn arg_value(flag: &str) -> Option<String> {
+    let mut args = std::env::args().skip_while(|arg| arg != flag);
+    args.nth(1)
+}
+
 fn main() {
+    if std::env::args().any(|arg| arg == "--dot") {
+        pt1::dot();
+        return;
+    }
+
+    // `--start` and `--goal` take regexes over node names, and apply to part 2 and `--replay`
+    let start = arg_value("--start");
+    let goal = arg_value("--goal");
+    if std::env::args().any(|arg| arg == "--replay") {
+        let start = start.as_deref().unwrap_or(pt1::START);
+        let goal = goal.as_deref().unwrap_or(pt1::END);
+        pt1::replay(start, goal).unwrap();
+        return;
+    }
+
     println!("Part 1:");
     pt1::pt1();
     println!("Part 2:");
-    pt2::pt2();
+    pt2::pt2(
+        start.as_deref().unwrap_or(pt2::START),
+        goal.as_deref().unwrap_or(pt2::END),
+    );
 }
@@ -0,0 +1,313 @@
+use anyhow::{anyhow, bail};
+use aoc_2023::graph::Graph;
+use aoc_2023::intern::Interner;
+use aoc_2023::numtheory::crt;
+use aoc_2023::parse;
+use itertools::Itertools;
+use regex::Regex;
+use std::convert::Infallible;
+use std::str::FromStr;
+
+pub const INPUT: &str = include_str!("input.dat");
+#[allow(dead_code)]
+pub const SAMPLE: &str = include_str!("sample.dat");
+
+// Picks out start or goal nodes by name
+pub fn matching(pattern: &str) -> anyhow::Result<impl Fn(&str) -> bool> {
+    let re = Regex::new(pattern)?;
+    Ok(move |name: &str| re.is_match(name))
+}
+
+#[derive(Debug, Clone)]
+pub struct Map {
+    instructions: Vec<Instruction>,
+    names: Interner,
+    links: Vec<Links>,
+}
+
+impl Map {
+    pub fn name(&self, id: usize) -> &str {
+        self.names.name(id)
+    }
+
+    pub fn nodes_matching(&self, pred: impl Fn(&str) -> bool) -> Vec<usize> {
+        self.names
+            .iter()
+            .filter(|(_, name)| pred(name))
+            .map(|(id, _)| id)
+            .collect()
+    }
+
+    pub fn instruction(&self, inst_ndx: usize) -> char {
+        match self.instructions[inst_ndx] {
+            Instruction::Left => 'L',
+            Instruction::Right => 'R',
+        }
+    }
+
+    fn step(&self, curr: usize, inst_ndx: usize) -> usize {
+        let links = self.links[curr];
+        match self.instructions[inst_ndx] {
+            Instruction::Left => links.left,
+            Instruction::Right => links.right,
+        }
+    }
+
+    // Steps from `start` to the first goal. A walk that hasn't reached one after visiting every
+    // (node, instruction index) state is going round a cycle without goals, and never will.
+    pub fn walk(&self, start: usize, is_goal: impl Fn(&str) -> bool) -> Option<usize> {
+        let num_insts = self.instructions.len();
+        let mut curr = start;
+        for step in 0..=self.links.len() * num_insts {
+            if is_goal(self.name(curr)) {
+                return Some(step);
+            }
+            curr = self.step(curr, step % num_insts);
+        }
+        None
+    }
+
+    // Every state visited from `start`, up to the first goal or until the walk starts repeating
+    pub fn replay(&self, start: usize, is_goal: impl Fn(&str) -> bool) -> Vec<Visit> {
+        let num_insts = self.instructions.len();
+        let mut seen = vec![false; self.links.len() * num_insts];
+        let mut visits = Vec::new();
+
+        let mut curr = start;
+        for step in 0.. {
+            let inst_ndx = step % num_insts;
+            let state = curr * num_insts + inst_ndx;
+            if seen[state] {
+                break;
+            }
+            seen[state] = true;
+
+            visits.push(Visit {
+                step,
+                node: curr,
+                inst_ndx,
+            });
+            if is_goal(self.name(curr)) {
+                break;
+            }
+            curr = self.step(curr, inst_ndx);
+        }
+
+        visits
+    }
+
+    // Walks from `start` until a (node, instruction index) state repeats
+    pub fn find_cycle(&self, start: usize, is_goal: impl Fn(&str) -> bool) -> Cycle {
+        let num_insts = self.instructions.len();
+        let mut first_seen = vec![None; self.links.len() * num_insts];
+        let mut goals = Vec::new();
+
+        let mut curr = start;
+        let mut step = 0;
+        loop {
+            let inst_ndx = step % num_insts;
+            let state = curr * num_insts + inst_ndx;
+            if let Some(entry) = first_seen[state] {
+                return Cycle {
+                    entry,
+                    length: step - entry,
+                    goals,
+                };
+            }
+            first_seen[state] = Some(step);
+
+            if is_goal(self.name(curr)) {
+                goals.push(step);
+            }
+            curr = self.step(curr, inst_ndx);
+            step += 1;
+        }
+    }
+
+    // The first step at which a walker from every start stands on a goal at once
+    pub fn count_steps(
+        &self,
+        is_start: impl Fn(&str) -> bool,
+        is_goal: impl Fn(&str) -> bool,
+    ) -> Option<usize> {
+        let cycles: Vec<Cycle> = self
+            .nodes_matching(is_start)
+            .into_iter()
+            .map(|p| self.find_cycle(p, &is_goal))
+            .collect();
+
+        // Goals passed before a walker enters its cycle only happen once, so each can be checked
+        // against every walker directly
+        let once = cycles
+            .iter()
+            .flat_map(|cycle| cycle.goals.iter().filter(|&&goal| goal < cycle.entry))
+            .copied()
+            .filter(|&step| cycles.iter().all(|cycle| cycle.at_goal(step)));
+
+        // Goals inside the cycles recur. Walkers are folded in one at a time, keeping only the
+        // progressions they all share so far, so the work grows with the number of shared
+        // progressions rather than with every combination of goals.
+        let recurring = cycles
+            .iter()
+            .map(Cycle::recurring_goals)
+            .reduce(|shared, goals| {
+                shared
+                    .iter()
+                    .cartesian_product(goals.iter())
+                    .filter_map(|(&a, &b)| crt([a, b]))
+                    .collect()
+            })
+            .into_iter()
+            .flatten()
+            .map(|(first, _)| first as usize);
+
+        once.chain(recurring).min()
+    }
+
+    pub fn to_graph(&self) -> Graph {
+        let mut graph = Graph::new();
+        for (id, name) in self.names.iter().sorted_by_key(|&(_, name)| name) {
+            let links = self.links[id];
+            let (left, right) = (self.name(links.left), self.name(links.right));
+            if left == right {
+                graph.add_edge(name, left, Some("L/R"));
+            } else {
+                graph.add_edge(name, left, Some("L"));
+                graph.add_edge(name, right, Some("R"));
+            }
+        }
+        graph
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct Visit {
+    pub step: usize,
+    pub node: usize,
+    pub inst_ndx: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct Cycle {
+    // Steps taken before the walk enters its cycle
+    pub entry: usize,
+    pub length: usize,
+    // Steps in the first `entry + length` at which the walk stands on a goal
+    pub goals: Vec<usize>,
+}
+
+impl Cycle {
+    fn at_goal(&self, step: usize) -> bool {
+        let step = if step < self.entry {
+            step
+        } else {
+            self.entry + (step - self.entry) % self.length
+        };
+        self.goals.binary_search(&step).is_ok()
+    }
+
+    // Each goal inside the cycle as the `(offset, period)` progression of steps it recurs at
+    fn recurring_goals(&self) -> Vec<(u64, u64)> {
+        self.goals
+            .iter()
+            .filter(|&&goal| goal >= self.entry)
+            .map(|&goal| (goal as u64, self.length as u64))
+            .collect()
+    }
+}
+
+impl FromStr for Map {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut lines = s.lines();
+        let instructions = lines
+            .next()
+            .unwrap()
+            .chars()
+            .map(|c| String::from(c).parse().unwrap())
+            .collect();
+
+        lines.next().unwrap();
+        let parsed: Vec<Node> = lines.map(str::parse).collect::<Result<_, _>>()?;
+
+        // Intern the defined names first so their ids index straight into `links`
+        let mut names = Interner::new();
+        for (ndx, node) in parsed.iter().enumerate() {
+            if names.intern(&node.name) != ndx {
+                bail!("node '{}' is defined more than once", node.name);
+            }
+        }
+
+        let lookup = |name: &str| {
+            names
+                .get(name)
+                .ok_or_else(|| anyhow!("node '{}' is never defined", name))
+        };
+        let links = parsed
+            .iter()
+            .map(|node| {
+                Ok(Links {
+                    left: lookup(&node.left)?,
+                    right: lookup(&node.right)?,
+                })
+            })
+            .collect::<anyhow::Result<_>>()?;
+
+        Ok(Map {
+            instructions,
+            names,
+            links,
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Instruction {
+    Left,
+    Right,
+}
+
+impl FromStr for Instruction {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == "L" {
+            Ok(Instruction::Left)
+        } else if s == "R" {
+            Ok(Instruction::Right)
+        } else {
+            panic!()
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Node {
+    name: String,
+    left: String,
+    right: String,
+}
+
+#[derive(Debug, Copy, Clone)]
+struct Links {
+    left: usize,
+    right: usize,
+}
+
+impl FromStr for Node {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, conns) = parse::named_tuple(s)?;
+        let [left, right] = conns[..] else {
+            return Err(anyhow!("expected two connections in '{}'", s));
+        };
+
+        Ok(Node {
+            name: name.to_string(),
+            left: left.to_string(),
+            right: right.to_string(),
+        })
+    }
+}
@@ -0,0 +1,137 @@
+use crate::polynomial::Polynomial;
+use anyhow::{bail, Context};
+use num::{BigInt, Zero};
+use std::str::FromStr;
+
+pub const INPUT: &str = include_str!("input.dat");
+#[allow(dead_code)]
+pub const SAMPLE: &str = include_str!("sample.dat");
+
+pub struct Puzzle {
+    input: Vec<BigInt>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Extrapolation {
+    pub degree: usize,
+    // Nearest first in both directions
+    pub forward: Vec<BigInt>,
+    pub backward: Vec<BigInt>,
+}
+
+impl Puzzle {
+    // Difference rows down to the first constant one, which needs at least two values to show
+    // it really is constant. A lone value is taken as degree 0, and two values as a straight
+    // line through them, since neither leaves enough to check.
+    fn find_histories(&self) -> anyhow::Result<Vec<Vec<BigInt>>> {
+        match self.input.len() {
+            0 => bail!("empty history"),
+            1 => return Ok(vec![self.input.clone()]),
+            _ => {}
+        }
+
+        let mut histories = vec![self.input.clone()];
+        loop {
+            let curr = histories.last().unwrap();
+            let settled = curr.len() >= 2 && curr.iter().all(|v| *v == curr[0]);
+            if settled || (curr.len() == 1 && self.input.len() == 2) {
+                return Ok(histories);
+            }
+            if curr.len() < 2 {
+                bail!(
+                    "history of {} values never settles to a constant difference",
+                    self.input.len()
+                );
+            }
+
+            let next = curr.windows(2).map(|w| &w[1] - &w[0]).collect();
+            histories.push(next);
+        }
+    }
+
+    // The polynomial through the history, with `n` counting from 0 at the first value
+    pub fn fit(&self) -> anyhow::Result<Polynomial> {
+        let leading: Vec<BigInt> = self
+            .find_histories()?
+            .iter()
+            .map(|h| h[0].clone())
+            .collect();
+        Ok(Polynomial::newton(&leading))
+    }
+
+    pub fn len(&self) -> usize {
+        self.input.len()
+    }
+
+    // The polynomial degree is the depth of the first constant row
+    pub fn extrapolate(&self, steps: usize) -> anyhow::Result<Extrapolation> {
+        let histories = self.find_histories()?;
+        let degree = histories.len() - 1;
+
+        // Extending the table by one column only needs each row's edge value
+        let mut lasts: Vec<BigInt> = histories
+            .iter()
+            .map(|h| h.last().unwrap().clone())
+            .collect();
+        let mut firsts: Vec<BigInt> = histories.iter().map(|h| h[0].clone()).collect();
+
+        let mut forward = Vec::with_capacity(steps);
+        let mut backward = Vec::with_capacity(steps);
+        for _ in 0..steps {
+            for row in (0..degree).rev() {
+                let below = lasts[row + 1].clone();
+                lasts[row] += below;
+                let below = firsts[row + 1].clone();
+                firsts[row] -= below;
+            }
+            forward.push(lasts[0].clone());
+            backward.push(firsts[0].clone());
+        }
+
+        Ok(Extrapolation {
+            degree,
+            forward,
+            backward,
+        })
+    }
+}
+
+impl FromStr for Puzzle {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let input = s
+            .split_ascii_whitespace()
+            .map(BigInt::from_str)
+            .collect::<Result<_, _>>()?;
+
+        Ok(Puzzle { input })
+    }
+}
+
+// The non-blank lines of the input, numbered from 1
+fn lines() -> impl Iterator<Item = (usize, &'static str)> {
+    INPUT
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line))
+        .filter(|(_, line)| !line.trim().is_empty())
+}
+
+pub fn puzzles() -> anyhow::Result<Vec<Puzzle>> {
+    lines()
+        .map(|(line_no, line)| line.parse().with_context(|| format!("line {}", line_no)))
+        .collect()
+}
+
+pub fn sum_extrapolated(pick: impl Fn(&Extrapolation) -> &BigInt) -> anyhow::Result<BigInt> {
+    let mut sum = BigInt::zero();
+    for (line_no, line) in lines() {
+        let extrapolation = line
+            .parse::<Puzzle>()
+            .and_then(|puzzle| puzzle.extrapolate(1))
+            .with_context(|| format!("line {}", line_no))?;
+        sum += pick(&extrapolation);
+    }
+    Ok(sum)
+}
@@ -1,7 +1,78 @@
+mod history;
+mod polynomial;
 mod pt1;
 mod pt2;
 
+use itertools::Itertools;
+use num::{BigInt, BigRational};
+
+// Handles `--extrapolate <k>`, printing k values each way for every history
+fn extrapolate_from_args() -> Option<anyhow::Result<()>> {
+    let mut args = std::env::args().skip_while(|arg| arg != "--extrapolate");
+    args.next()?;
+
+    let mut extrapolate = || {
+        let steps = args.next().map_or(Ok(1), |arg| arg.parse())?;
+        for (i, puzzle) in history::puzzles()?.iter().enumerate() {
+            let extrapolation = puzzle.extrapolate(steps)?;
+            println!(
+                "{}: degree {}, back {}, forward {}",
+                i + 1,
+                extrapolation.degree,
+                extrapolation.backward.iter().format(" "),
+                extrapolation.forward.iter().format(" ")
+            );
+        }
+        Ok(())
+    };
+    Some(extrapolate())
+}
+
+// Handles `--fit [index]`, printing each history's polynomial. The value just past the history
+// is checked against the difference table, and `index` picks another point to evaluate at.
+fn fit_from_args() -> Option<anyhow::Result<()>> {
+    let mut args = std::env::args().skip_while(|arg| arg != "--fit");
+    args.next()?;
+
+    let mut fit = || {
+        let index: Option<BigInt> = args.next().map(|arg| arg.parse()).transpose()?;
+        for (i, puzzle) in history::puzzles()?.iter().enumerate() {
+            let polynomial = puzzle.fit()?;
+            let next = polynomial.eval(&BigInt::from(puzzle.len()));
+            let table = &puzzle.extrapolate(1)?.forward[0];
+            let agrees = if next == BigRational::from(table.clone()) {
+                "agrees"
+            } else {
+                "DISAGREES"
+            };
+
+            print!(
+                "{}: p(n) = {}, next {} ({} with the table)",
+                i + 1,
+                polynomial,
+                next,
+                agrees
+            );
+            if let Some(index) = &index {
+                print!(", p({}) = {}", index, polynomial.eval(index));
+            }
+            println!();
+        }
+        Ok(())
+    };
+    Some(fit())
+}
+
 fn main() {
+    if let Some(result) = fit_from_args() {
+        result.unwrap();
+        return;
+    }
+    if let Some(result) = extrapolate_from_args() {
+        result.unwrap();
+        return;
+    }
+
     println!("Part 1:");
     pt1::pt1();
     println!("Part 2:");
@@ -0,0 +1,114 @@
+use num::{BigInt, BigRational, One, Signed, Zero};
+use std::fmt;
+
+// A polynomial in `n` with exact rational coefficients, lowest power first.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Polynomial {
+    coeffs: Vec<BigRational>,
+}
+
+impl Polynomial {
+    fn constant(c: BigRational) -> Polynomial {
+        Polynomial { coeffs: vec![c] }
+    }
+
+    // Newton's forward form: p(n) = sum over j of (the first value of difference row j) * C(n, j)
+    pub fn newton(leading_differences: &[BigInt]) -> Polynomial {
+        let mut result = Polynomial::constant(BigRational::zero());
+        // C(n, j) as a polynomial, built up one factor (n - j) / (j + 1) at a time
+        let mut binomial = Polynomial::constant(BigRational::one());
+
+        for (j, diff) in leading_differences.iter().enumerate() {
+            result = result.add(&binomial.scale(&BigRational::from(diff.clone())));
+
+            let j = BigInt::from(j);
+            let factor = Polynomial {
+                coeffs: vec![BigRational::from(-&j), BigRational::one()],
+            };
+            binomial = binomial
+                .mul(&factor)
+                .scale(&BigRational::new(BigInt::one(), j + 1));
+        }
+
+        result.trimmed()
+    }
+
+    pub fn eval(&self, n: &BigInt) -> BigRational {
+        let n = BigRational::from(n.clone());
+        self.coeffs
+            .iter()
+            .rev()
+            .fold(BigRational::zero(), |acc, c| acc * &n + c)
+    }
+
+    fn add(&self, other: &Polynomial) -> Polynomial {
+        let len = self.coeffs.len().max(other.coeffs.len());
+        let zero = BigRational::zero();
+        let coeffs = (0..len)
+            .map(|i| self.coeffs.get(i).unwrap_or(&zero) + other.coeffs.get(i).unwrap_or(&zero))
+            .collect();
+        Polynomial { coeffs }
+    }
+
+    fn mul(&self, other: &Polynomial) -> Polynomial {
+        let mut coeffs = vec![BigRational::zero(); self.coeffs.len() + other.coeffs.len() - 1];
+        for (i, a) in self.coeffs.iter().enumerate() {
+            for (j, b) in other.coeffs.iter().enumerate() {
+                coeffs[i + j] += a * b;
+            }
+        }
+        Polynomial { coeffs }
+    }
+
+    fn scale(&self, k: &BigRational) -> Polynomial {
+        Polynomial {
+            coeffs: self.coeffs.iter().map(|c| c * k).collect(),
+        }
+    }
+
+    fn trimmed(mut self) -> Polynomial {
+        while self.coeffs.len() > 1 && self.coeffs.last().unwrap().is_zero() {
+            self.coeffs.pop();
+        }
+        self
+    }
+}
+
+// Prints highest power first, e.g. "1/2 n^2 + 3/2 n + 1"
+impl fmt::Display for Polynomial {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let terms: Vec<(usize, &BigRational)> = self
+            .coeffs
+            .iter()
+            .enumerate()
+            .rev()
+            .filter(|(_, c)| !c.is_zero())
+            .collect();
+        if terms.is_empty() {
+            return write!(f, "0");
+        }
+
+        for (i, (power, c)) in terms.into_iter().enumerate() {
+            let sign = if c.is_negative() { "-" } else { "+" };
+            match (i, sign) {
+                (0, "-") => write!(f, "-")?,
+                (0, _) => {}
+                _ => write!(f, " {} ", sign)?,
+            }
+
+            let c = c.abs();
+            let unit = c.is_one() && power > 0;
+            if !unit {
+                write!(f, "{}", c)?;
+            }
+            match power {
+                0 => {}
+                1 if unit => write!(f, "n")?,
+                1 => write!(f, " n")?,
+                _ if unit => write!(f, "n^{}", power)?,
+                _ => write!(f, " n^{}", power)?,
+            }
+        }
+        Ok(())
+    }
+}
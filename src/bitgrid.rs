@@ -0,0 +1,138 @@
+use std::ops::{BitAndAssign, BitOrAssign};
+
+// A grid of booleans packed 64 to a word. Each row starts on a fresh word, so row operations
+// work on whole words and bits past the width are always zero.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct BitGrid {
+    width: usize,
+    height: usize,
+    words_per_row: usize,
+    words: Vec<u64>,
+}
+
+impl BitGrid {
+    pub fn new(width: usize, height: usize) -> Self {
+        let words_per_row = width.div_ceil(64);
+        BitGrid {
+            width,
+            height,
+            words_per_row,
+            words: vec![0; words_per_row * height],
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    fn index(&self, x: usize, y: usize) -> (usize, u64) {
+        assert!(
+            x < self.width && y < self.height,
+            "({}, {}) is out of bounds",
+            x,
+            y
+        );
+        (y * self.words_per_row + x / 64, 1 << (x % 64))
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> bool {
+        let (word, mask) = self.index(x, y);
+        self.words[word] & mask != 0
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, value: bool) {
+        let (word, mask) = self.index(x, y);
+        if value {
+            self.words[word] |= mask;
+        } else {
+            self.words[word] &= !mask;
+        }
+    }
+
+    // Sets the bit, returning whether it was previously unset.
+    pub fn insert(&mut self, x: usize, y: usize) -> bool {
+        let (word, mask) = self.index(x, y);
+        let was_unset = self.words[word] & mask == 0;
+        self.words[word] |= mask;
+        was_unset
+    }
+
+    pub fn clear(&mut self) {
+        self.words.fill(0);
+    }
+
+    pub fn count_ones(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    pub fn row_words(&self, y: usize) -> &[u64] {
+        &self.words[y * self.words_per_row..(y + 1) * self.words_per_row]
+    }
+
+    // Callers must leave the bits past the width unset.
+    pub fn row_words_mut(&mut self, y: usize) -> &mut [u64] {
+        &mut self.words[y * self.words_per_row..(y + 1) * self.words_per_row]
+    }
+
+    // The bits of word `i` of a row that lie within the width.
+    pub fn width_mask(&self, i: usize) -> u64 {
+        let bits = self.width.saturating_sub(i * 64).min(64);
+        if bits == 64 {
+            u64::MAX
+        } else {
+            (1 << bits) - 1
+        }
+    }
+
+    pub fn count_row(&self, y: usize) -> usize {
+        self.row_words(y)
+            .iter()
+            .map(|w| w.count_ones() as usize)
+            .sum()
+    }
+
+    pub fn clear_row(&mut self, y: usize) {
+        self.words[y * self.words_per_row..(y + 1) * self.words_per_row].fill(0);
+    }
+
+    // The positions of the set bits, row by row.
+    pub fn iter_ones(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.words.iter().enumerate().flat_map(move |(ndx, &word)| {
+            let y = ndx / self.words_per_row;
+            let x_base = (ndx % self.words_per_row) * 64;
+            let mut word = word;
+            std::iter::from_fn(move || {
+                if word == 0 {
+                    return None;
+                }
+                let bit = word.trailing_zeros() as usize;
+                word &= word - 1;
+                Some((x_base + bit, y))
+            })
+        })
+    }
+}
+
+impl BitOrAssign<&BitGrid> for BitGrid {
+    fn bitor_assign(&mut self, other: &BitGrid) {
+        assert_eq!((self.width, self.height), (other.width, other.height));
+        self.words
+            .iter_mut()
+            .zip(other.words.iter())
+            .for_each(|(w, o)| *w |= o);
+    }
+}
+
+impl BitAndAssign<&BitGrid> for BitGrid {
+    fn bitand_assign(&mut self, other: &BitGrid) {
+        assert_eq!((self.width, self.height), (other.width, other.height));
+        self.words
+            .iter_mut()
+            .zip(other.words.iter())
+            .for_each(|(w, o)| *w &= o);
+    }
+}
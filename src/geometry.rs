@@ -0,0 +1,138 @@
+use num::Integer;
+
+pub type Point = (i64, i64);
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Orientation {
+    Clockwise,
+    CounterClockwise,
+    Degenerate,
+}
+
+// A closed polygon whose vertices all lie on integer coordinates. The closing edge from the
+// last vertex back to the first is implicit.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Polygon {
+    vertices: Vec<Point>,
+}
+
+impl Polygon {
+    pub fn new(mut vertices: Vec<Point>) -> Self {
+        if vertices.len() > 1 && vertices.first() == vertices.last() {
+            vertices.pop();
+        }
+
+        Polygon { vertices }
+    }
+
+    pub fn vertices(&self) -> &[Point] {
+        &self.vertices
+    }
+
+    pub fn edges(&self) -> impl Iterator<Item = (Point, Point)> + '_ {
+        let n = self.vertices.len();
+        (0..n).map(move |i| (self.vertices[i], self.vertices[(i + 1) % n]))
+    }
+
+    // Twice the signed area from the shoelace formula, positive for counter-clockwise
+    // polygons in a y-up coordinate system. Kept doubled so it stays an integer.
+    pub fn double_signed_area(&self) -> i64 {
+        self.edges()
+            .map(|((x1, y1), (x2, y2))| x1 * y2 - x2 * y1)
+            .sum()
+    }
+
+    pub fn area(&self) -> f64 {
+        self.double_signed_area().abs() as f64 / 2.0
+    }
+
+    pub fn orientation(&self) -> Orientation {
+        match self.double_signed_area().signum() {
+            1 => Orientation::CounterClockwise,
+            -1 => Orientation::Clockwise,
+            _ => Orientation::Degenerate,
+        }
+    }
+
+    // The number of lattice points lying on the edges.
+    pub fn boundary_points(&self) -> u64 {
+        self.edges()
+            .map(|((x1, y1), (x2, y2))| (x2 - x1).abs().gcd(&(y2 - y1).abs()) as u64)
+            .sum()
+    }
+
+    // The number of lattice points strictly inside, by Pick's theorem: A = I + B/2 - 1.
+    // Degenerate polygons, with every vertex on one line, have none.
+    pub fn interior_points(&self) -> u64 {
+        let double_area = self.double_signed_area().unsigned_abs();
+        (double_area + 2).saturating_sub(self.boundary_points()) / 2
+    }
+
+    // The number of lattice points inside or on the boundary.
+    pub fn lattice_points(&self) -> u64 {
+        self.interior_points() + self.boundary_points()
+    }
+
+    // Whether any two edges touch other than adjacent edges meeting at their shared vertex.
+    pub fn is_self_intersecting(&self) -> bool {
+        // Repeated vertices give zero-length edges, which would touch both neighbours. Dropping
+        // them leaves the edges on either side meeting at the repeated point.
+        let edges: Vec<_> = self.edges().filter(|(a, b)| a != b).collect();
+        let n = edges.len();
+
+        for i in 0..n {
+            for j in (i + 1)..n {
+                let (a1, a2) = edges[i];
+                let (b1, b2) = edges[j];
+
+                let adjacent = j == i + 1 || (i == 0 && j == n - 1);
+                if !adjacent {
+                    if segments_touch(a1, a2, b1, b2) {
+                        return true;
+                    }
+                    continue;
+                }
+
+                // Adjacent edges share a vertex; they only intersect if they fold back
+                // over each other.
+                let (shared, a_other, b_other) = if a2 == b1 { (a2, a1, b2) } else { (a1, a2, b1) };
+                if cross(shared, a_other, b_other) == 0
+                    && dot(shared, a_other, b_other) > 0
+                    && n > 2
+                {
+                    return true;
+                }
+            }
+        }
+
+        false
+    }
+}
+
+fn cross(o: Point, a: Point, b: Point) -> i64 {
+    (a.0 - o.0) * (b.1 - o.1) - (a.1 - o.1) * (b.0 - o.0)
+}
+
+fn dot(o: Point, a: Point, b: Point) -> i64 {
+    (a.0 - o.0) * (b.0 - o.0) + (a.1 - o.1) * (b.1 - o.1)
+}
+
+fn on_segment(p: Point, a: Point, b: Point) -> bool {
+    p.0 >= a.0.min(b.0) && p.0 <= a.0.max(b.0) && p.1 >= a.1.min(b.1) && p.1 <= a.1.max(b.1)
+}
+
+fn segments_touch(a1: Point, a2: Point, b1: Point, b2: Point) -> bool {
+    let d1 = cross(b1, b2, a1).signum();
+    let d2 = cross(b1, b2, a2).signum();
+    let d3 = cross(a1, a2, b1).signum();
+    let d4 = cross(a1, a2, b2).signum();
+
+    if d1 * d2 < 0 && d3 * d4 < 0 {
+        return true;
+    }
+
+    (d1 == 0 && on_segment(a1, b1, b2))
+        || (d2 == 0 && on_segment(a2, b1, b2))
+        || (d3 == 0 && on_segment(b1, a1, a2))
+        || (d4 == 0 && on_segment(b2, a1, a2))
+}
@@ -0,0 +1,198 @@
+use crate::intern::Interner;
+use std::fmt::Write;
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Edge {
+    pub to: usize,
+    pub label: Option<String>,
+}
+
+// A directed graph over named nodes. Names are interned, so nodes are addressed by dense ids.
+#[derive(Debug, Clone, Default)]
+pub struct Graph {
+    names: Interner,
+    labels: Vec<Option<String>>,
+    out_edges: Vec<Vec<Edge>>,
+    in_edges: Vec<Vec<usize>>,
+}
+
+impl Graph {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_node(&mut self, name: &str) -> usize {
+        let id = self.names.intern(name);
+        if id == self.out_edges.len() {
+            self.labels.push(None);
+            self.out_edges.push(Vec::new());
+            self.in_edges.push(Vec::new());
+        }
+        id
+    }
+
+    // Sets the text shown for the node in DOT output, e.g. "%a" for a flip-flop named "a".
+    pub fn set_label(&mut self, id: usize, label: &str) {
+        self.labels[id] = Some(label.to_string());
+    }
+
+    pub fn add_edge(&mut self, from: &str, to: &str, label: Option<&str>) {
+        let from = self.add_node(from);
+        let to = self.add_node(to);
+
+        self.out_edges[from].push(Edge {
+            to,
+            label: label.map(str::to_string),
+        });
+        self.in_edges[to].push(from);
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    pub fn id(&self, name: &str) -> Option<usize> {
+        self.names.get(name)
+    }
+
+    pub fn name(&self, id: usize) -> &str {
+        self.names.name(id)
+    }
+
+    pub fn edges(&self, id: usize) -> &[Edge] {
+        &self.out_edges[id]
+    }
+
+    pub fn successors(&self, id: usize) -> impl Iterator<Item = usize> + '_ {
+        self.out_edges[id].iter().map(|edge| edge.to)
+    }
+
+    pub fn predecessors(&self, id: usize) -> impl Iterator<Item = usize> + '_ {
+        self.in_edges[id].iter().copied()
+    }
+
+    // Tarjan's algorithm. Components come out in reverse topological order: no component has
+    // an edge into one listed after it.
+    pub fn strongly_connected_components(&self) -> Vec<Vec<usize>> {
+        let n = self.len();
+        let mut index = vec![usize::MAX; n];
+        let mut lowlink = vec![0; n];
+        let mut on_stack = vec![false; n];
+        let mut stack = Vec::new();
+        let mut components = Vec::new();
+        let mut next_index = 0;
+
+        for root in 0..n {
+            if index[root] != usize::MAX {
+                continue;
+            }
+
+            // Each frame is a node and the position of the next out-edge to explore.
+            let mut frames = vec![(root, 0)];
+            index[root] = next_index;
+            lowlink[root] = next_index;
+            next_index += 1;
+            stack.push(root);
+            on_stack[root] = true;
+
+            while let Some((node, edge_ndx)) = frames.pop() {
+                if let Some(edge) = self.out_edges[node].get(edge_ndx) {
+                    frames.push((node, edge_ndx + 1));
+
+                    let next = edge.to;
+                    if index[next] == usize::MAX {
+                        index[next] = next_index;
+                        lowlink[next] = next_index;
+                        next_index += 1;
+                        stack.push(next);
+                        on_stack[next] = true;
+                        frames.push((next, 0));
+                    } else if on_stack[next] {
+                        lowlink[node] = lowlink[node].min(index[next]);
+                    }
+                    continue;
+                }
+
+                if let Some(&(parent, _)) = frames.last() {
+                    lowlink[parent] = lowlink[parent].min(lowlink[node]);
+                }
+
+                if lowlink[node] == index[node] {
+                    let mut component = Vec::new();
+                    loop {
+                        let member = stack.pop().unwrap();
+                        on_stack[member] = false;
+                        component.push(member);
+                        if member == node {
+                            break;
+                        }
+                    }
+                    components.push(component);
+                }
+            }
+        }
+
+        components
+    }
+
+    // Kahn's algorithm. Returns `None` if the graph has a cycle.
+    pub fn topological_order(&self) -> Option<Vec<usize>> {
+        let mut in_degree: Vec<usize> = self.in_edges.iter().map(Vec::len).collect();
+        let mut ready: Vec<usize> = (0..self.len()).filter(|&id| in_degree[id] == 0).collect();
+        let mut order = Vec::with_capacity(self.len());
+
+        while let Some(id) = ready.pop() {
+            order.push(id);
+            for next in self.successors(id) {
+                in_degree[next] -= 1;
+                if in_degree[next] == 0 {
+                    ready.push(next);
+                }
+            }
+        }
+
+        (order.len() == self.len()).then_some(order)
+    }
+
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph {\n");
+
+        for (id, name) in self.names.iter() {
+            let label = self.labels[id].as_deref().unwrap_or(name);
+            writeln!(
+                dot,
+                "    \"{}\" [label=\"{}\"];",
+                escape(name),
+                escape(label)
+            )
+            .unwrap();
+        }
+
+        for (from, edges) in self.out_edges.iter().enumerate() {
+            for edge in edges {
+                write!(
+                    dot,
+                    "    \"{}\" -> \"{}\"",
+                    escape(self.name(from)),
+                    escape(self.name(edge.to))
+                )
+                .unwrap();
+                if let Some(label) = &edge.label {
+                    write!(dot, " [label=\"{}\"]", escape(label)).unwrap();
+                }
+                dot.push_str(";\n");
+            }
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+fn escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
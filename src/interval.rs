@@ -0,0 +1,202 @@
+use num::{BigUint, PrimInt};
+
+// A half-open range of integers, `start..end`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct Interval<T> {
+    pub start: T,
+    pub end: T,
+}
+
+impl<T: PrimInt> Interval<T> {
+    pub fn new(start: T, end: T) -> Self {
+        Interval { start, end }
+    }
+
+    // Saturates at `T::max_value()` rather than overflowing; use `checked_from_len` on input.
+    pub fn from_len(start: T, len: T) -> Self {
+        Interval {
+            start,
+            end: start.saturating_add(len),
+        }
+    }
+
+    pub fn checked_from_len(start: T, len: T) -> Option<Self> {
+        Some(Interval {
+            start,
+            end: start.checked_add(&len)?,
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start >= self.end
+    }
+
+    pub fn len(&self) -> T {
+        if self.is_empty() {
+            T::zero()
+        } else {
+            self.end - self.start
+        }
+    }
+
+    pub fn contains(&self, x: T) -> bool {
+        self.start <= x && x < self.end
+    }
+
+    pub fn intersect(&self, other: &Interval<T>) -> Option<Interval<T>> {
+        let start = T::max(self.start, other.start);
+        let end = T::min(self.end, other.end);
+        let intersection = Interval { start, end };
+
+        (!intersection.is_empty()).then_some(intersection)
+    }
+
+    // The parts of `self` that are not covered by `other`, in ascending order.
+    pub fn subtract(&self, other: &Interval<T>) -> Vec<Interval<T>> {
+        if self.intersect(other).is_none() {
+            return if self.is_empty() { vec![] } else { vec![*self] };
+        }
+
+        [
+            Interval::new(self.start, other.start),
+            Interval::new(other.end, self.end),
+        ]
+        .into_iter()
+        .filter(|i| !i.is_empty())
+        .collect()
+    }
+
+    // Splits into the parts below and at-or-above `at`.
+    pub fn split_at(&self, at: T) -> (Option<Interval<T>>, Option<Interval<T>>) {
+        let at = at.clamp(self.start, T::max(self.start, self.end));
+        let lower = Interval::new(self.start, at);
+        let upper = Interval::new(at, self.end);
+
+        (
+            (!lower.is_empty()).then_some(lower),
+            (!upper.is_empty()).then_some(upper),
+        )
+    }
+}
+
+// Merges overlapping and touching intervals, returning them sorted and disjoint.
+pub fn normalize<T: PrimInt>(intervals: impl IntoIterator<Item = Interval<T>>) -> Vec<Interval<T>> {
+    let mut intervals: Vec<_> = intervals.into_iter().filter(|i| !i.is_empty()).collect();
+    intervals.sort();
+
+    let mut merged: Vec<Interval<T>> = Vec::with_capacity(intervals.len());
+    for interval in intervals {
+        match merged.last_mut() {
+            Some(last) if interval.start <= last.end => last.end = T::max(last.end, interval.end),
+            _ => merged.push(interval),
+        }
+    }
+
+    merged
+}
+
+// An axis-aligned box made of one interval per dimension.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Cuboid<T, const N: usize> {
+    pub axes: [Interval<T>; N],
+}
+
+impl<T: PrimInt, const N: usize> Cuboid<T, N> {
+    pub fn new(axes: [Interval<T>; N]) -> Self {
+        Cuboid { axes }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.axes.iter().any(|axis| axis.is_empty())
+    }
+
+    // The number of integer points inside the box, which can exceed any fixed-width integer.
+    pub fn count(&self) -> BigUint {
+        self.axes
+            .iter()
+            .map(|axis| BigUint::from(axis.len().to_u128().unwrap()))
+            .product()
+    }
+
+    pub fn contains(&self, point: [T; N]) -> bool {
+        self.axes
+            .iter()
+            .zip(point)
+            .all(|(axis, x)| axis.contains(x))
+    }
+
+    pub fn intersect(&self, other: &Cuboid<T, N>) -> Option<Cuboid<T, N>> {
+        let mut axes = self.axes;
+        for (axis, other_axis) in axes.iter_mut().zip(other.axes.iter()) {
+            *axis = axis.intersect(other_axis)?;
+        }
+
+        Some(Cuboid { axes })
+    }
+
+    // Restricts a single dimension to `interval`.
+    pub fn constrain(&self, dim: usize, interval: &Interval<T>) -> Option<Cuboid<T, N>> {
+        let mut axes = self.axes;
+        axes[dim] = axes[dim].intersect(interval)?;
+
+        Some(Cuboid { axes })
+    }
+
+    pub fn split_at(&self, dim: usize, at: T) -> (Option<Cuboid<T, N>>, Option<Cuboid<T, N>>) {
+        let (lower, upper) = self.axes[dim].split_at(at);
+        let with_axis = |interval: Interval<T>| {
+            let mut axes = self.axes;
+            axes[dim] = interval;
+            Cuboid { axes }
+        };
+
+        (lower.map(with_axis), upper.map(with_axis))
+    }
+
+    // The parts of `self` not covered by `other`, as at most `2 * N` disjoint boxes.
+    pub fn subtract(&self, other: &Cuboid<T, N>) -> Vec<Cuboid<T, N>> {
+        let Some(overlap) = self.intersect(other) else {
+            return if self.is_empty() { vec![] } else { vec![*self] };
+        };
+
+        let mut pieces = Vec::new();
+        let mut rest = *self;
+        for dim in 0..N {
+            let (below, middle) = rest.split_at(dim, overlap.axes[dim].start);
+            pieces.extend(below);
+
+            let (middle, above) = middle.unwrap().split_at(dim, overlap.axes[dim].end);
+            pieces.extend(above);
+
+            rest = middle.unwrap();
+        }
+
+        pieces
+    }
+}
+
+// Rewrites a set of possibly overlapping boxes as disjoint boxes covering the same points.
+pub fn disjoint_union<T: PrimInt, const N: usize>(
+    boxes: impl IntoIterator<Item = Cuboid<T, N>>,
+) -> Vec<Cuboid<T, N>> {
+    let mut disjoint: Vec<Cuboid<T, N>> = Vec::new();
+    for cuboid in boxes {
+        let mut fresh = vec![cuboid];
+        for existing in disjoint.iter() {
+            fresh = fresh
+                .into_iter()
+                .flat_map(|piece| piece.subtract(existing))
+                .collect();
+        }
+        disjoint.append(&mut fresh);
+    }
+
+    disjoint
+}
+
+// Counts the points covered by a set of boxes, which must already be disjoint.
+pub fn count_disjoint<'a, T: PrimInt + 'a, const N: usize>(
+    boxes: impl IntoIterator<Item = &'a Cuboid<T, N>>,
+) -> BigUint {
+    boxes.into_iter().map(|cuboid| cuboid.count()).sum()
+}
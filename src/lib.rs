@@ -0,0 +1,11 @@
+pub mod bitgrid;
+pub mod geometry;
+pub mod graph;
+pub mod grid;
+pub mod intern;
+pub mod interval;
+pub mod memo;
+pub mod numtheory;
+pub mod parse;
+pub mod render;
+pub mod trace;
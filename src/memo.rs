@@ -0,0 +1,152 @@
+use crate::trace;
+use std::borrow::Borrow;
+use std::collections::{HashMap, VecDeque};
+use std::hash::Hash;
+use std::iter::Sum;
+use std::ops::Add;
+
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub struct MemoStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub evictions: u64,
+}
+
+impl MemoStats {
+    pub fn hit_rate(&self) -> f64 {
+        let lookups = self.hits + self.misses;
+        if lookups == 0 {
+            0.0
+        } else {
+            self.hits as f64 / lookups as f64
+        }
+    }
+
+    // Prints the statistics to stderr when trace mode is on.
+    pub fn report(&self, name: &str) {
+        if trace::enabled() {
+            eprintln!(
+                "[memo] {}: {} hits, {} misses ({:.1}% hit rate), {} evictions",
+                name,
+                self.hits,
+                self.misses,
+                self.hit_rate() * 100.0,
+                self.evictions
+            );
+        }
+    }
+}
+
+impl Add for MemoStats {
+    type Output = MemoStats;
+
+    fn add(self, other: MemoStats) -> MemoStats {
+        MemoStats {
+            hits: self.hits + other.hits,
+            misses: self.misses + other.misses,
+            evictions: self.evictions + other.evictions,
+        }
+    }
+}
+
+impl Sum for MemoStats {
+    fn sum<I: Iterator<Item = MemoStats>>(iter: I) -> MemoStats {
+        iter.fold(MemoStats::default(), Add::add)
+    }
+}
+
+// A cache for recursive solvers. With a limit set, the oldest entry is evicted whenever the
+// cache would grow past the limit, which bounds memory at the cost of recomputation.
+#[derive(Debug, Clone)]
+pub struct Memo<K, V> {
+    cache: HashMap<K, V>,
+    // Keys in insertion order, kept only when there is a limit
+    order: VecDeque<K>,
+    limit: Option<usize>,
+    stats: MemoStats,
+}
+
+impl<K: Eq + Hash + Clone, V: Clone> Default for Memo<K, V> {
+    fn default() -> Self {
+        Memo {
+            cache: HashMap::new(),
+            order: VecDeque::new(),
+            limit: None,
+            stats: MemoStats::default(),
+        }
+    }
+}
+
+impl<K: Eq + Hash + Clone, V: Clone> Memo<K, V> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_limit(limit: usize) -> Self {
+        Memo {
+            limit: Some(limit),
+            ..Self::default()
+        }
+    }
+
+    pub fn get<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        let value = self.cache.get(key).cloned();
+        if value.is_some() {
+            self.stats.hits += 1;
+        } else {
+            self.stats.misses += 1;
+        }
+        value
+    }
+
+    pub fn insert(&mut self, key: K, value: V) {
+        if let Some(existing) = self.cache.get_mut(&key) {
+            *existing = value;
+            return;
+        }
+
+        if let Some(limit) = self.limit {
+            // A limit of zero turns caching off
+            if limit == 0 {
+                return;
+            }
+            while self.cache.len() >= limit {
+                let Some(oldest) = self.order.pop_front() else {
+                    break;
+                };
+                self.cache.remove(&oldest);
+                self.stats.evictions += 1;
+            }
+            self.order.push_back(key.clone());
+        }
+        self.cache.insert(key, value);
+    }
+
+    // Returns the cached value for `key`, or computes it with `f`. The memo is handed to `f`
+    // so that it can recurse through the same cache.
+    pub fn cached(&mut self, key: K, f: impl FnOnce(&mut Self) -> V) -> V {
+        if let Some(value) = self.get(&key) {
+            return value;
+        }
+
+        let value = f(self);
+        self.insert(key, value.clone());
+        value
+    }
+
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+
+    pub fn stats(&self) -> MemoStats {
+        self.stats
+    }
+}
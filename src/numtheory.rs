@@ -0,0 +1,88 @@
+use num::Integer;
+
+pub fn gcd<T: Integer, I: IntoIterator<Item = T>>(inputs: I) -> T {
+    inputs.into_iter().fold(T::zero(), |acc, x| acc.gcd(&x))
+}
+
+pub fn lcm<T: Integer, I: IntoIterator<Item = T>>(inputs: I) -> T {
+    inputs.into_iter().fold(T::one(), |acc, x| acc.lcm(&x))
+}
+
+// Returns `(g, x, y)` such that `a * x + b * y == g == gcd(a, b)`.
+pub fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    if b == 0 {
+        (a.abs(), a.signum(), 0)
+    } else {
+        let (g, x, y) = extended_gcd(b, a.rem_euclid(b));
+        (g, y, x - a.div_euclid(b) * y)
+    }
+}
+
+// The multiplicative inverse of `a` modulo `m`, if `a` and `m` are coprime.
+pub fn mod_inverse(a: i128, m: i128) -> Option<i128> {
+    let (g, x, _) = extended_gcd(a.rem_euclid(m), m);
+    (g == 1).then(|| x.rem_euclid(m))
+}
+
+// Each `(offset, period)` pair describes the times `offset + k * period` for `k >= 0`, such as
+// the steps at which a cycle that is entered after `offset` steps revisits some state. Finds the
+// first time shared by every pair, and the period with which the shared times repeat.
+//
+// The periods need not be coprime. Returns `None` if the progressions never coincide, or if the
+// first shared time or the combined period does not fit in a `u64`.
+pub fn crt<I: IntoIterator<Item = (u64, u64)>>(pairs: I) -> Option<(u64, u64)> {
+    let mut offset: i128 = 0;
+    let mut period: i128 = 1;
+    let mut min_start: i128 = 0;
+
+    for (a, n) in pairs {
+        let (a, n) = (a as i128, n as i128);
+        min_start = min_start.max(a);
+
+        // A zero period pins the progression to a single time.
+        if period == 0 {
+            let hits = if n == 0 {
+                a == offset
+            } else {
+                a <= offset && (offset - a) % n == 0
+            };
+            if !hits {
+                return None;
+            }
+            continue;
+        }
+        if n == 0 {
+            if (a - offset).rem_euclid(period) != 0 {
+                return None;
+            }
+            offset = a;
+            period = 0;
+            continue;
+        }
+
+        let (g, p, _) = extended_gcd(period, n);
+        let diff = a - offset;
+        if diff % g != 0 {
+            return None;
+        }
+
+        let lcm = (period / g).checked_mul(n)?;
+        let modulus = (n / g) as u128;
+        let step =
+            ((diff / g).rem_euclid(n / g) as u128 * p.rem_euclid(n / g) as u128 % modulus) as i128;
+        offset = offset
+            .checked_add(period.checked_mul(step)?)?
+            .rem_euclid(lcm);
+        period = lcm;
+    }
+
+    if period != 0 && offset < min_start {
+        let periods = (min_start - offset + period - 1) / period;
+        offset = offset.checked_add(periods.checked_mul(period)?)?;
+    }
+    if offset < min_start {
+        return None;
+    }
+
+    Some((u64::try_from(offset).ok()?, u64::try_from(period).ok()?))
+}
@@ -0,0 +1,142 @@
+use anyhow::{anyhow, Context, Result};
+use std::str::FromStr;
+
+// Parses every whitespace-separated token, e.g. "79 14 55 13".
+pub fn numbers<T>(s: &str) -> Result<Vec<T>>
+where
+    T: FromStr,
+    T::Err: std::error::Error + Send + Sync + 'static,
+{
+    s.split_ascii_whitespace()
+        .map(|n| n.parse().with_context(|| format!("invalid number '{}'", n)))
+        .collect()
+}
+
+// Splits a line like "Time:  7  15   30" into its label and numbers.
+pub fn labeled_numbers<T>(line: &str) -> Result<(&str, Vec<T>)>
+where
+    T: FromStr,
+    T::Err: std::error::Error + Send + Sync + 'static,
+{
+    let (label, rest) = line
+        .split_once(':')
+        .ok_or_else(|| anyhow!("expected 'label: numbers', got '{}'", line))?;
+
+    Ok((label.trim(), numbers(rest)?))
+}
+
+// Like `labeled_numbers`, but joins the digits into a single number, ignoring the spaces
+// between them.
+pub fn labeled_joined_number<T>(line: &str) -> Result<(&str, T)>
+where
+    T: FromStr,
+    T::Err: std::error::Error + Send + Sync + 'static,
+{
+    let (label, rest) = line
+        .split_once(':')
+        .ok_or_else(|| anyhow!("expected 'label: number', got '{}'", line))?;
+    let joined: String = rest.split_ascii_whitespace().collect();
+    let number = joined
+        .parse()
+        .with_context(|| format!("invalid number '{}'", joined))?;
+
+    Ok((label.trim(), number))
+}
+
+// Splits the input into the sections separated by blank lines.
+pub fn blocks(input: &str) -> impl Iterator<Item = &str> {
+    let mut blocks = Vec::new();
+    let mut start = None;
+    let mut end = 0;
+    let mut offset = 0;
+
+    for line in input.split_inclusive('\n') {
+        let line_start = offset;
+        offset += line.len();
+
+        if line.trim().is_empty() {
+            if let Some(block_start) = start.take() {
+                blocks.push(&input[block_start..end]);
+            }
+        } else {
+            start.get_or_insert(line_start);
+            end = line_start + line.trim_end().len();
+        }
+    }
+    if let Some(block_start) = start {
+        blocks.push(&input[block_start..end]);
+    }
+
+    blocks.into_iter()
+}
+
+// Splits a line like "%a -> b, c" into its source and targets.
+pub fn adjacency(line: &str) -> Result<(&str, Vec<&str>)> {
+    let (from, to) = line
+        .split_once("->")
+        .ok_or_else(|| anyhow!("expected 'name -> targets', got '{}'", line))?;
+    let to = to
+        .split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .collect();
+
+    Ok((from.trim(), to))
+}
+
+// Splits a line like "AAA = (BBB, CCC)" into its name and the tuple members.
+pub fn named_tuple(line: &str) -> Result<(&str, Vec<&str>)> {
+    let (name, tuple) = line
+        .split_once('=')
+        .ok_or_else(|| anyhow!("expected 'name = (a, b)', got '{}'", line))?;
+    let tuple = tuple.trim();
+    let members = tuple
+        .strip_prefix('(')
+        .and_then(|t| t.strip_suffix(')'))
+        .ok_or_else(|| anyhow!("expected a parenthesised tuple, got '{}'", tuple))?
+        .split(',')
+        .map(str::trim)
+        .collect();
+
+    Ok((name.trim(), members))
+}
+
+pub fn char_grid(input: &str) -> Vec<Vec<char>> {
+    input
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(|line| line.chars().collect())
+        .collect()
+}
+
+// Parses each character of a rectangular grid, failing on unknown tiles or ragged rows.
+pub fn grid<T>(input: &str) -> Result<Vec<Vec<T>>>
+where
+    T: TryFrom<char>,
+    T::Error: std::error::Error + Send + Sync + 'static,
+{
+    let grid = input
+        .lines()
+        .filter(|line| !line.is_empty())
+        .enumerate()
+        .map(|(y, line)| {
+            line.chars()
+                .enumerate()
+                .map(|(x, c)| {
+                    T::try_from(c)
+                        .with_context(|| format!("invalid tile '{}' at ({}, {})", c, x, y))
+                })
+                .collect::<Result<Vec<T>>>()
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    if let Some(row) = grid.iter().find(|row| row.len() != grid[0].len()) {
+        return Err(anyhow!(
+            "ragged grid: expected rows of width {}, found {}",
+            grid[0].len(),
+            row.len()
+        ));
+    }
+
+    Ok(grid)
+}
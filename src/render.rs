@@ -0,0 +1,155 @@
+use crate::grid::Grid;
+use anyhow::{anyhow, Context, Result};
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::hash::Hash;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+pub type Rgb = [u8; 3];
+
+pub const BLACK: Rgb = [0, 0, 0];
+pub const WHITE: Rgb = [255, 255, 255];
+pub const GREY: Rgb = [128, 128, 128];
+pub const RED: Rgb = [220, 50, 47];
+pub const GREEN: Rgb = [133, 153, 0];
+pub const BLUE: Rgb = [38, 139, 210];
+pub const YELLOW: Rgb = [181, 137, 0];
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Image {
+    width: usize,
+    height: usize,
+    pixels: Vec<Rgb>,
+}
+
+impl Image {
+    // Draws each cell as a `scale` by `scale` block of the colour picked for it.
+    pub fn from_grid<T>(grid: &Grid<T>, scale: usize, color: impl Fn(&T) -> Rgb) -> Self {
+        let scale = scale.max(1);
+        let width = grid.width() * scale;
+        let height = grid.height() * scale;
+
+        let pixels = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(|(x, y)| color(grid.get(x / scale, y / scale)))
+            .collect();
+
+        Image {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn write_ppm(&self, path: &Path) -> Result<()> {
+        let file = File::create(path).with_context(|| format!("creating {}", path.display()))?;
+        let mut out = BufWriter::new(file);
+
+        write!(out, "P6\n{} {}\n255\n", self.width, self.height)?;
+        for pixel in &self.pixels {
+            out.write_all(pixel)?;
+        }
+        out.flush()?;
+
+        Ok(())
+    }
+
+    pub fn write_png(&self, path: &Path) -> Result<()> {
+        let file = File::create(path).with_context(|| format!("creating {}", path.display()))?;
+
+        let mut encoder =
+            png::Encoder::new(BufWriter::new(file), self.width as u32, self.height as u32);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+
+        let data: Vec<u8> = self.pixels.iter().flatten().copied().collect();
+        encoder.write_header()?.write_image_data(&data)?;
+
+        Ok(())
+    }
+
+    // Picks the format from the extension, `.ppm` or `.png`.
+    pub fn write(&self, path: &Path) -> Result<()> {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("ppm") => self.write_ppm(path),
+            Some("png") => self.write_png(path),
+            _ => Err(anyhow!("unsupported image format: {}", path.display())),
+        }
+    }
+}
+
+// Maps tile values to colours, falling back to a default for anything unlisted.
+#[derive(Debug, Clone)]
+pub struct Palette<T> {
+    colors: HashMap<T, Rgb>,
+    default: Rgb,
+}
+
+impl<T: Eq + Hash> Palette<T> {
+    pub fn new(entries: impl IntoIterator<Item = (T, Rgb)>, default: Rgb) -> Self {
+        Palette {
+            colors: entries.into_iter().collect(),
+            default,
+        }
+    }
+
+    pub fn color(&self, value: &T) -> Rgb {
+        self.colors.get(value).copied().unwrap_or(self.default)
+    }
+}
+
+// Writes numbered images, `<prefix>_00000.<ext>`, `<prefix>_00001.<ext>`, ..., into a directory.
+#[derive(Debug)]
+pub struct FrameWriter {
+    dir: PathBuf,
+    prefix: String,
+    extension: String,
+    next: usize,
+}
+
+impl FrameWriter {
+    pub fn new(dir: &Path, prefix: &str, extension: &str) -> Result<Self> {
+        fs::create_dir_all(dir).with_context(|| format!("creating {}", dir.display()))?;
+
+        Ok(FrameWriter {
+            dir: dir.to_path_buf(),
+            prefix: prefix.to_string(),
+            extension: extension.to_string(),
+            next: 0,
+        })
+    }
+
+    pub fn write(&mut self, image: &Image) -> Result<PathBuf> {
+        let path = self.dir.join(format!(
+            "{}_{:05}.{}",
+            self.prefix, self.next, self.extension
+        ));
+        image.write(&path)?;
+        self.next += 1;
+
+        Ok(path)
+    }
+
+    pub fn frames_written(&self) -> usize {
+        self.next
+    }
+}
+
+// The directory given as `--render <dir>` on the command line, if any.
+pub fn render_dir_from_args() -> Option<PathBuf> {
+    let mut args = std::env::args().skip_while(|arg| arg != "--render");
+    args.next()?;
+    Some(
+        args.next()
+            .map_or_else(|| PathBuf::from("render"), PathBuf::from),
+    )
+}
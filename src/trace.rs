@@ -0,0 +1,8 @@
+use std::sync::OnceLock;
+
+// Trace mode is switched on by setting `AOC_TRACE` in the environment. Trace output goes to
+// stderr so it never mixes with the answers.
+pub fn enabled() -> bool {
+    static ENABLED: OnceLock<bool> = OnceLock::new();
+    *ENABLED.get_or_init(|| std::env::var_os("AOC_TRACE").is_some())
+}